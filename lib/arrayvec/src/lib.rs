@@ -31,6 +31,26 @@ impl<T> fmt::Debug for CapacityError<T> {
     }
 }
 
+/// Error returned by [`ArrayVec::fill_from_reader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillError {
+    /// The `ArrayVec` doesn't have enough spare capacity for the requested bytes.
+    Capacity,
+    /// The source holds fewer than the requested bytes.
+    UnexpectedEof,
+}
+
+impl Error for FillError {}
+
+impl fmt::Display for FillError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FillError::Capacity => f.write_str("insufficient capacity"),
+            FillError::UnexpectedEof => f.write_str("unexpected end of source"),
+        }
+    }
+}
+
 /// A vector with a fixed capacity.
 ///
 /// The `ArrayVec` is a vector backed by a fixed size array. Elements are stored inline in the vector
@@ -134,6 +154,39 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
         unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
     }
 
+    /// Returns a mutable reference to the first element, or `None` if the vector is empty.
+    pub const fn first_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().first_mut()
+    }
+
+    /// Returns a mutable reference to the last element, or `None` if the vector is empty.
+    pub const fn last_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+
+    /// Returns mutable references to many elements at once.
+    ///
+    /// Returns `None` if any of the indices is out of bounds or if an index appears more than once.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [usize; N]) -> Option<[&mut T; N]> {
+        self.as_mut_slice().get_disjoint_mut(indices).ok()
+    }
+
+    /// Returns `true` if the vector contains an element equal to `x`.
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.as_slice().contains(x)
+    }
+
+    /// Returns the index of the first element equal to `x`, or `None` if there is none.
+    pub fn position(&self, x: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        self.iter().position(|element| element == x)
+    }
+
     /// Push `element` to the end of the vector.
     ///
     /// # Panics
@@ -174,6 +227,40 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
         self.len += 1;
     }
 
+    /// Inserts `element` into an already sorted vector, keeping it sorted, and returns the index it
+    /// was inserted at.
+    ///
+    /// The position is found using a binary search. Elements comparing equal to `element` keep
+    /// their relative order, `element` is inserted after all of them.
+    ///
+    /// If the vector is not sorted, the position is unspecified but the element is still inserted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(CapacityError)` with the element if the `ArrayVec` is full.
+    pub fn insert_sorted(&mut self, element: T) -> Result<usize, CapacityError<T>>
+    where
+        T: Ord,
+    {
+        if self.is_full() {
+            return Err(CapacityError(element));
+        }
+
+        let index = self.partition_point(|probe| probe <= &element);
+        let len = self.len();
+
+        // Safety: we checked above that there is room for one more element, and `index <= len`,
+        // so shifting `index..len` up by one stays within capacity.
+        unsafe {
+            let slot = self.as_mut_ptr().add(index);
+            ptr::copy(slot, slot.add(1), len - index);
+            ptr::write(slot, element);
+            self.len += 1;
+        }
+
+        Ok(index)
+    }
+
     /// Removes the last element from a vector and returns it, or [`None`] if it
     /// is empty.
     ///
@@ -227,6 +314,66 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
         }
     }
 
+    /// Swaps the elements at indices `a` and `b`.
+    ///
+    /// Other slice operations such as `rotate_left` and `rotate_right` are available
+    /// through `Deref<Target = [T]>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b);
+    }
+
+    /// Reverses the order of the elements in the vector, in place.
+    #[inline]
+    pub fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+
+    /// Reorders the elements in place so that all elements for which `pred` returns `true` come
+    /// before all elements for which it returns `false`, and returns the number of elements for
+    /// which it returned `true`.
+    ///
+    /// The relative order of elements within each partition is **not** preserved. `pred` is called
+    /// exactly once for each element.
+    pub fn partition<F>(&mut self, mut pred: F) -> usize
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let slice = self.as_mut_slice();
+        let mut left = 0;
+        let mut right = slice.len();
+
+        // Everything before `left` belongs to the front and everything from `right` on to the
+        // back. Each element is tested only when one of the two cursors first reaches it.
+        loop {
+            while left < right && pred(&slice[left]) {
+                left += 1;
+            }
+            if left == right {
+                return left;
+            }
+
+            // `slice[left]` was tested and belongs to the back, find an element for the front
+            // to swap it with without testing `slice[left]` again
+            loop {
+                right -= 1;
+                if right == left {
+                    return left;
+                }
+                if pred(&slice[right]) {
+                    break;
+                }
+            }
+
+            slice.swap(left, right);
+            left += 1;
+        }
+    }
+
     /// Remove all elements in the vector.
     pub fn clear(&mut self) {
         let len = self.len;
@@ -382,6 +529,26 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
         }
     }
 
+    /// Removes the elements from `from` to the end of the vector, returning a
+    /// double-ended iterator over them.
+    ///
+    /// This is like [`truncate`](Self::truncate) but hands out the removed
+    /// elements instead of dropping them, and is equivalent to
+    /// `self.drain(from..)`. If the iterator is dropped before being fully
+    /// consumed, it drops the remaining removed elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is greater than the length of the vector.
+    pub fn drain_tail(&mut self, from: usize) -> Drain<'_, T, CAP> {
+        assert!(
+            from <= self.len(),
+            "drain_tail start (is {from}) should be <= len (is {})",
+            self.len()
+        );
+        self.drain(from..)
+    }
+
     /// Shortens the vector, keeping the first `len` elements and dropping
     /// the rest
     pub fn truncate(&mut self, new_len: usize) {
@@ -398,6 +565,39 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
         }
     }
 
+    /// Splits the vector into two at the given index.
+    ///
+    /// Returns a new `ArrayVec` containing the elements in the range
+    /// `[at, len)`. After the call, the original vector will be left containing
+    /// the elements `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        #[cold]
+        fn assert_failed(at: usize, len: usize) -> ! {
+            panic!("`at` split index (is {at}) should be <= len (is {len})");
+        }
+
+        let len = self.len();
+        if at > len {
+            assert_failed(at, len);
+        }
+
+        let mut other = Self::new();
+        // Safety: `at..len` is initialized and `other` has the same capacity, so the tail fits.
+        // Setting `self.len` to `at` hands ownership of the moved elements to `other`, so
+        // nothing is dropped twice.
+        unsafe {
+            self.len = at;
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), len - at);
+            other.len = len - at;
+        }
+        other
+    }
+
     /// Returns the remaining spare capacity of the vector as a slice of
     /// `MaybeUninit<T>`.
     pub fn spare_capacity_mut(&mut self) -> &mut [MaybeUninit<T>] {
@@ -439,6 +639,73 @@ impl<T, const CAP: usize> ArrayVec<T, CAP> {
 
         Ok(())
     }
+
+    /// Extend the `ArrayVec` with elements from the provided slice, copying them in bulk.
+    ///
+    /// This behaves like [`ArrayVec::extend_from_slice`] but is restricted to `Copy` types which
+    /// lets it use a single `memcpy` instead of cloning element by element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `ArrayVec` does not have enough capacity to accommodate
+    /// the elements.
+    pub fn extend_from_copy_slice(&mut self, slice: &[T])
+    where
+        T: Copy,
+    {
+        self.try_extend_from_copy_slice(slice).unwrap();
+    }
+
+    /// Extend the `ArrayVec` with elements from the provided slice, copying them in bulk.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `CapacityError` if the `ArrayVec` does not have enough capacity to accommodate
+    /// the elements.
+    pub fn try_extend_from_copy_slice(&mut self, other: &[T]) -> Result<(), CapacityError<()>>
+    where
+        T: Copy,
+    {
+        if self.remaining_capacity() < other.len() {
+            return Err(CapacityError(()));
+        }
+
+        // Safety: we checked above that the spare capacity can hold `other.len()` elements, and
+        // `other` can't overlap our spare capacity since we hold a mutable borrow of `self`.
+        unsafe {
+            ptr::copy_nonoverlapping(
+                other.as_ptr(),
+                self.spare_capacity_mut().as_mut_ptr().cast::<T>(),
+                other.len(),
+            );
+        }
+        self.len += other.len();
+
+        Ok(())
+    }
+}
+
+impl<const CAP: usize> ArrayVec<u8, CAP> {
+    /// Copies `n` bytes off the front of `src` into the `ArrayVec`, advancing `src` past them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FillError::Capacity`] if the `ArrayVec` does not have enough capacity to
+    /// accommodate `n` more bytes, and [`FillError::UnexpectedEof`] if `src` is shorter than `n`
+    /// bytes. Neither the `ArrayVec` nor `src` are modified in either case.
+    pub fn fill_from_reader(&mut self, src: &mut &[u8], n: usize) -> Result<(), FillError> {
+        if self.remaining_capacity() < n {
+            return Err(FillError::Capacity);
+        }
+        let Some((head, tail)) = src.split_at_checked(n) else {
+            return Err(FillError::UnexpectedEof);
+        };
+
+        self.extend_from_copy_slice(head);
+        *src = tail;
+
+        Ok(())
+    }
 }
 
 impl<T, const CAP: usize> fmt::Debug for ArrayVec<T, CAP>
@@ -896,6 +1163,326 @@ mod tests {
         assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
     }
 
+    #[test]
+    fn extend_from_copy_slice_matches_clone_path() {
+        let bytes: [u8; 4096] = core::array::from_fn(|i| u8::try_from(i % 251).unwrap());
+
+        let mut copied: ArrayVec<u8, 4100> = ArrayVec::new();
+        copied.push(0xff);
+        copied.extend_from_copy_slice(&bytes);
+
+        let mut cloned: ArrayVec<u8, 4100> = ArrayVec::new();
+        cloned.push(0xff);
+        cloned.extend_from_slice(&bytes);
+
+        assert_eq!(copied.len(), 4097);
+        assert_eq!(copied.as_slice(), cloned.as_slice());
+    }
+
+    #[test]
+    fn try_extend_from_copy_slice_fails_without_capacity() {
+        let mut vec: ArrayVec<i32, 3> = ArrayVec::new();
+        vec.push(1);
+        assert!(vec.try_extend_from_copy_slice(&[2, 3, 4]).is_err());
+        // nothing was copied
+        assert_eq!(vec.as_slice(), &[1]);
+        assert!(vec.try_extend_from_copy_slice(&[2, 3]).is_ok());
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn extend_from_copy_slice_panics_when_insufficient_capacity() {
+        let mut vec: ArrayVec<i32, 3> = ArrayVec::new();
+        vec.extend_from_copy_slice(&[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fill_from_reader_copies_prefix() {
+        let mut vec: ArrayVec<u8, 8> = ArrayVec::new();
+        let mut src: &[u8] = &[1, 2, 3, 4, 5];
+
+        vec.fill_from_reader(&mut src, 2).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 2]);
+        assert_eq!(src, &[3, 4, 5]);
+
+        vec.fill_from_reader(&mut src, 0).unwrap();
+        assert_eq!(vec.as_slice(), &[1, 2]);
+        assert_eq!(src, &[3, 4, 5]);
+    }
+
+    #[test]
+    fn fill_from_reader_up_to_capacity() {
+        let mut vec: ArrayVec<u8, 4> = ArrayVec::new();
+        vec.push(0);
+        let mut src: &[u8] = &[1, 2, 3, 4];
+
+        vec.fill_from_reader(&mut src, 3).unwrap();
+        assert!(vec.is_full());
+        assert_eq!(vec.as_slice(), &[0, 1, 2, 3]);
+        assert_eq!(src, &[4]);
+    }
+
+    #[test]
+    fn fill_from_reader_fails_without_capacity() {
+        let mut vec: ArrayVec<u8, 4> = ArrayVec::new();
+        vec.push(0);
+        let mut src: &[u8] = &[1, 2, 3, 4];
+
+        assert_eq!(vec.fill_from_reader(&mut src, 4), Err(FillError::Capacity));
+        // neither side was touched
+        assert_eq!(vec.as_slice(), &[0]);
+        assert_eq!(src, &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn fill_from_reader_fails_on_short_source() {
+        let mut vec: ArrayVec<u8, 8> = ArrayVec::new();
+        vec.push(0);
+        let mut src: &[u8] = &[1, 2];
+
+        assert_eq!(
+            vec.fill_from_reader(&mut src, 3),
+            Err(FillError::UnexpectedEof)
+        );
+        // neither side was touched
+        assert_eq!(vec.as_slice(), &[0]);
+        assert_eq!(src, &[1, 2]);
+    }
+
+    #[test]
+    fn insert_sorted_into_empty() {
+        let mut vec: ArrayVec<i32, 4> = ArrayVec::new();
+        assert_eq!(vec.insert_sorted(5).unwrap(), 0);
+        assert_eq!(vec.as_slice(), &[5]);
+    }
+
+    #[test]
+    fn insert_sorted_keeps_order() {
+        let mut vec: ArrayVec<i32, 8> = ArrayVec::new();
+        let indices: Vec<_> = [5, 1, 9, 3, 7, 0]
+            .into_iter()
+            .map(|element| vec.insert_sorted(element).unwrap())
+            .collect();
+        assert_eq!(indices, [0, 0, 2, 1, 3, 0]);
+        assert_eq!(vec.as_slice(), &[0, 1, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn insert_sorted_after_equals() {
+        // ordered by the first field only, the second one tells equal elements apart
+        #[derive(Debug, PartialEq, Eq)]
+        struct Keyed(u32, char);
+        impl PartialOrd for Keyed {
+            fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Keyed {
+            fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+                self.0.cmp(&other.0)
+            }
+        }
+
+        let mut vec: ArrayVec<Keyed, 8> = ArrayVec::new();
+        vec.insert_sorted(Keyed(1, 'a')).unwrap();
+        vec.insert_sorted(Keyed(2, 'a')).unwrap();
+        assert_eq!(vec.insert_sorted(Keyed(1, 'b')).unwrap(), 1);
+        assert_eq!(vec.insert_sorted(Keyed(1, 'c')).unwrap(), 2);
+
+        let order: Vec<_> = vec.iter().map(|k| (k.0, k.1)).collect();
+        assert_eq!(order, [(1, 'a'), (1, 'b'), (1, 'c'), (2, 'a')]);
+    }
+
+    #[test]
+    fn insert_sorted_fails_when_full() {
+        let mut vec: ArrayVec<i32, 2> = ArrayVec::new();
+        vec.insert_sorted(2).unwrap();
+        vec.insert_sorted(1).unwrap();
+        let err = vec.insert_sorted(0).unwrap_err();
+        assert_eq!(err.0, 0);
+        assert_eq!(vec.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn drain_tail_yields_tail() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4, 5]);
+        let tail: Vec<_> = vec.drain_tail(2).collect();
+        assert_eq!(tail, [3, 4, 5]);
+        assert_eq!(vec.as_slice(), &[1, 2]);
+    }
+
+    #[test]
+    fn drain_tail_dropped_early() {
+        let rc = std::rc::Rc::new(());
+        let mut vec: ArrayVec<_, 10> = ArrayVec::new();
+        for _ in 0..5 {
+            vec.push(rc.clone());
+        }
+
+        let mut tail = vec.drain_tail(1);
+        let first = tail.next().unwrap();
+        drop(tail);
+
+        // the unconsumed elements were dropped, only `vec[0]` and `first` remain
+        assert_eq!(vec.len(), 1);
+        assert_eq!(std::rc::Rc::strong_count(&rc), 3);
+        drop(first);
+        assert_eq!(std::rc::Rc::strong_count(&rc), 2);
+    }
+
+    #[test]
+    fn drain_tail_at_len_is_noop() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(vec.drain_tail(3).next(), None);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "drain_tail start (is 4) should be <= len (is 3)")]
+    fn drain_tail_out_of_bounds() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        let _ = vec.drain_tail(4);
+    }
+
+    #[test]
+    fn contains_and_position() {
+        let mut vec: ArrayVec<&str, 10> = ArrayVec::new();
+        vec.extend_from_slice(&["a", "b", "c", "b"]);
+
+        assert!(vec.contains(&"b"));
+        assert!(!vec.contains(&"d"));
+        assert_eq!(vec.position(&"b"), Some(1));
+        assert_eq!(vec.position(&"c"), Some(2));
+        assert_eq!(vec.position(&"d"), None);
+
+        let empty: ArrayVec<&str, 10> = ArrayVec::new();
+        assert!(!empty.contains(&"a"));
+        assert_eq!(empty.position(&"a"), None);
+    }
+
+    #[test]
+    fn first_last_mut() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        assert_eq!(vec.first_mut(), None);
+        assert_eq!(vec.last_mut(), None);
+
+        vec.extend_from_slice(&[1, 2, 3]);
+        *vec.first_mut().unwrap() = 10;
+        *vec.last_mut().unwrap() = 30;
+        assert_eq!(vec.as_slice(), &[10, 2, 30]);
+    }
+
+    #[test]
+    fn get_disjoint_mut() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4]);
+
+        let [a, b] = vec.get_disjoint_mut([3, 0]).unwrap();
+        core::mem::swap(a, b);
+        assert_eq!(vec.as_slice(), &[4, 2, 3, 1]);
+
+        assert!(vec.get_disjoint_mut([1, 1]).is_none());
+        assert!(vec.get_disjoint_mut([0, 4]).is_none());
+        assert_eq!(vec.get_disjoint_mut::<0>([]), Some([]));
+    }
+
+    #[test]
+    fn partition_mixed() {
+        let mut vec: ArrayVec<u32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7]);
+
+        let mut calls = 0;
+        let pivot = vec.partition(|x| {
+            calls += 1;
+            x % 2 == 0
+        });
+        assert_eq!(pivot, 3);
+        assert_eq!(calls, 7);
+        assert!(vec[..pivot].iter().all(|x| x % 2 == 0));
+        assert!(vec[pivot..].iter().all(|x| x % 2 != 0));
+
+        let mut sorted = vec.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted.as_slice(), &[1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn partition_calls_pred_once_per_element() {
+        fn calls(elements: &[u32]) -> usize {
+            let mut vec: ArrayVec<u32, 10> = ArrayVec::new();
+            vec.extend_from_slice(elements);
+            let mut calls = 0;
+            vec.partition(|x| {
+                calls += 1;
+                x % 2 == 0
+            });
+            calls
+        }
+
+        assert_eq!(calls(&[]), 0);
+        assert_eq!(calls(&[1]), 1);
+        assert_eq!(calls(&[2]), 1);
+        assert_eq!(calls(&[2, 1, 3]), 3);
+        assert_eq!(calls(&[1, 3, 2, 4]), 4);
+        assert_eq!(calls(&[1, 2, 1, 2, 1, 2]), 6);
+    }
+
+    #[test]
+    fn partition_all_or_nothing() {
+        let mut vec: ArrayVec<u32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(vec.partition(|_| true), 3);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+        assert_eq!(vec.partition(|_| false), 0);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+
+        let mut empty: ArrayVec<u32, 10> = ArrayVec::new();
+        assert_eq!(empty.partition(|_| true), 0);
+    }
+
+    #[test]
+    fn swap_exchanges_elements() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        vec.swap(0, 2);
+        assert_eq!(vec.as_slice(), &[3, 2, 1]);
+        vec.swap(1, 1);
+        assert_eq!(vec.as_slice(), &[3, 2, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_panics_out_of_bounds() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        // index 3 is within capacity but not within the live elements
+        vec.swap(0, 3);
+    }
+
+    #[test]
+    fn reverse_even_length() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4]);
+        vec.reverse();
+        assert_eq!(vec.as_slice(), &[4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn reverse_odd_length() {
+        let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4, 5]);
+        vec.reverse();
+        assert_eq!(vec.as_slice(), &[5, 4, 3, 2, 1]);
+
+        let mut empty: ArrayVec<i32, 10> = ArrayVec::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
     #[test]
     #[should_panic]
     fn extend_from_slice_panics_when_insufficient_capacity() {
@@ -953,6 +1540,73 @@ mod tests {
         assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 3);
     }
 
+    #[test]
+    fn split_off_at_zero() {
+        let mut vec: ArrayVec<i32, 5> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        let tail = vec.split_off(0);
+        assert!(vec.is_empty());
+        assert_eq!(tail.as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn split_off_in_the_middle() {
+        let mut vec: ArrayVec<i32, 5> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3, 4, 5]);
+        let mut tail = vec.split_off(2);
+        assert_eq!(vec.as_slice(), &[1, 2]);
+        assert_eq!(tail.as_slice(), &[3, 4, 5]);
+
+        // both halves remain fully usable
+        vec.push(6);
+        tail.push(7);
+        assert_eq!(vec.as_slice(), &[1, 2, 6]);
+        assert_eq!(tail.as_slice(), &[3, 4, 5, 7]);
+    }
+
+    #[test]
+    fn split_off_at_len() {
+        let mut vec: ArrayVec<i32, 5> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        let tail = vec.split_off(3);
+        assert_eq!(vec.as_slice(), &[1, 2, 3]);
+        assert!(tail.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_off_panics_past_len() {
+        let mut vec: ArrayVec<i32, 5> = ArrayVec::new();
+        vec.extend_from_slice(&[1, 2, 3]);
+        let _ = vec.split_off(4);
+    }
+
+    #[test]
+    fn split_off_drops_every_element_once() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static DROP_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        struct DropCounter;
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                DROP_COUNT.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut vec: ArrayVec<DropCounter, 5> = ArrayVec::new();
+        for _ in 0..5 {
+            vec.push(DropCounter);
+        }
+
+        let tail = vec.split_off(2);
+        assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 0);
+
+        drop(tail);
+        assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 3);
+        drop(vec);
+        assert_eq!(DROP_COUNT.load(Ordering::SeqCst), 5);
+    }
+
     #[test]
     fn drain_restores_tail_on_early_drop() {
         let mut vec: ArrayVec<i32, 10> = ArrayVec::new();
@@ -21,6 +21,10 @@ pub enum Error {
     InvalidPropertyValue,
     InvalidCellSize,
     InvalidPath,
+    InvalidPhandle(u32),
+    MalformedHeader,
+    UnsupportedVersion(u32),
+    TooDeep,
 }
 
 impl From<core::str::Utf8Error> for Error {
@@ -58,7 +62,11 @@ impl fmt::Display for Error {
             Error::InvalidPropertyValue => write!(f, "invalid property value"),
             Error::InvalidCellSize => write!(f, "invalid cell size"),
             Error::InvalidPath => write!(f, "invalid path"),
+            Error::InvalidPhandle(phandle) => write!(f, "no node with phandle {phandle}"),
+            Error::MalformedHeader => write!(f, "malformed header"),
+            Error::UnsupportedVersion(version) => write!(f, "unsupported FDT version {version}"),
             Error::TryFromSlice(err) => write!(f, "failed to parse slice: {err}"),
+            Error::TooDeep => write!(f, "node is nested too deeply"),
         }
     }
 }
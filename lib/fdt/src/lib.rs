@@ -7,16 +7,20 @@
 
 #![cfg_attr(not(test), no_std)]
 
+extern crate alloc;
+
 mod error;
 mod parser;
+mod writer;
 
 use core::ffi::CStr;
-use core::{fmt, slice};
+use core::{fmt, ptr, slice};
 
 use fallible_iterator::FallibleIterator;
 
 pub use crate::error::Error;
 use crate::parser::{BigEndianToken, Parser, StringsBlock, StructsBlock};
+pub use crate::writer::FdtWriter;
 
 const DTB_MAGIC: u32 = 0xD00D_FEED;
 
@@ -25,6 +29,10 @@ const DTB_MAGIC: u32 = 0xD00D_FEED;
 /// stack allocate the buffer.
 const MAX_TRACKED_DEPTH: usize = 32;
 
+/// Maximum `#interrupt-cells` of an interrupt controller that [`Interrupts`]
+/// can decode.
+const MAX_INTERRUPT_CELLS: usize = 4;
+
 pub struct Fdt<'dt> {
     data: &'dt [u32],
     reservations: &'dt [u32],
@@ -58,6 +66,7 @@ pub struct Header {
     pub structs_size: u32,
 }
 
+#[derive(Clone)]
 pub struct Node<'dt> {
     name: &'dt CStr,
     raw: &'dt [u32],
@@ -80,6 +89,43 @@ pub struct Property<'dt> {
     pub raw: &'dt [u8],
 }
 
+impl Header {
+    /// Size in bytes of the header layout we parse, introduced with version 17.
+    const SIZE: u32 = 40;
+    /// The newest FDT version whose layout we understand.
+    const VERSION: u32 = 17;
+
+    /// Checks that the version is one we can parse and that all blocks lie
+    /// within `total_size`.
+    fn validate(&self) -> Result<(), Error> {
+        // Version 17 introduced `size_dt_struct`, which we rely on. Newer
+        // versions are fine as long as they stay backwards compatible with it.
+        if self.version < Self::VERSION
+            || self.last_compatible_version > Self::VERSION
+            || self.last_compatible_version > self.version
+        {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+
+        let within = |offset: u32, size: u32| {
+            offset
+                .checked_add(size)
+                .is_some_and(|end| offset >= Self::SIZE && end <= self.total_size)
+        };
+        if self.total_size < Self::SIZE
+            || !self.structs_offset.is_multiple_of(4)
+            || !self.memory_reserve_map_offset.is_multiple_of(8)
+            || !within(self.structs_offset, self.structs_size)
+            || !within(self.strings_offset, self.strings_size)
+            || !within(self.memory_reserve_map_offset, 0)
+        {
+            return Err(Error::MalformedHeader);
+        }
+
+        Ok(())
+    }
+}
+
 impl fmt::Debug for Fdt<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Fdt")
@@ -100,7 +146,9 @@ impl<'dt> Fdt<'dt> {
 
         if header.magic != DTB_MAGIC {
             return Err(Error::BadMagic);
-        } else if data.len() < (header.total_size / 4) as usize {
+        }
+        header.validate()?;
+        if data.len() < (header.total_size / 4) as usize {
             return Err(Error::UnexpectedEof);
         }
 
@@ -127,9 +175,10 @@ impl<'dt> Fdt<'dt> {
                 .ok_or(Error::UnexpectedEof)?,
         );
 
+        // The reservation block is terminated by an all-zero entry, so all we know about its end
+        // is that it lies within the blob.
         let reservations_start = header.memory_reserve_map_offset as usize / 4;
-        let reservations_end =
-            structs_start + ((header.total_size - header.memory_reserve_map_offset) as usize / 4);
+        let reservations_end = header.total_size as usize / 4;
         let reservations = data
             .get(reservations_start..reservations_end)
             .ok_or(Error::UnexpectedEof)?;
@@ -211,7 +260,11 @@ impl<'dt> Fdt<'dt> {
     /// Find a node by its absolute path (e.g. `/chosen`, `/cpus/cpu@0`).
     ///
     /// Each segment matches a node's name; if the segment contains `@`, the
-    /// unit address must match too. Returns `Ok(None)` if no such node exists.
+    /// unit address must match too. A segment without a unit address also
+    /// matches a node that has one (e.g. `uart` matches `uart@10000000`), but
+    /// only if that node is the sole sibling with this name. The path `/`
+    /// resolves to the root node. Returns `Ok(None)` if no such node exists or
+    /// the path is ambiguous.
     ///
     /// # Errors
     ///
@@ -219,33 +272,151 @@ impl<'dt> Fdt<'dt> {
     /// rooted at `/`.
     pub fn find_node(&self, path: &str) -> Result<Option<Node<'dt>>, Error> {
         let rest = path.strip_prefix('/').ok_or(Error::InvalidPath)?;
-        let mut segments = rest.split('/').filter(|s| !s.is_empty());
-        let mut want = segments.next().ok_or(Error::InvalidPath)?;
 
         let mut nodes = self.nodes()?;
-        let mut target = 1;
-        while let Some((depth, node)) = nodes.next()? {
-            if depth < target {
+        let mut found = self.root.clone();
+        for (want, depth) in rest.split('/').filter(|s| !s.is_empty()).zip(1..) {
+            let Some(node) = find_child(&mut nodes, depth, want)? else {
                 return Ok(None);
-            }
-            if depth > target {
-                continue;
-            }
-            let name = node.name()?;
-            let matches = match want.split_once('@') {
-                Some((n, a)) => n == name.name && name.unit_address == Some(a),
-                None => want == name.name,
-            };
-            if !matches {
-                continue;
-            }
-            let Some(next) = segments.next() else {
-                return Ok(Some(node));
             };
-            want = next;
-            target += 1;
+            found = node;
         }
-        Ok(None)
+        Ok(Some(found))
+    }
+
+    /// Returns an iterator over all nodes whose `compatible` list contains at
+    /// least one of the strings in `with`, in tree order.
+    ///
+    /// Nodes without a `compatible` property are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing the FDT fails. The iterator itself fails if
+    /// walking the tree fails or a `compatible` property is not valid UTF-8.
+    pub fn find_compatible<'a>(
+        &'a self,
+        with: &'a [&str],
+    ) -> Result<impl FallibleIterator<Item = Node<'dt>, Error = Error> + 'a, Error> {
+        Ok(self.nodes()?.map(|(_, node)| Ok(node)).filter(move |node| {
+            let Some(compatible) = node.find_property("compatible")? else {
+                return Ok(false);
+            };
+            Ok(compatible.as_strlist()?.any(|c| with.contains(&c)))
+        }))
+    }
+
+    /// Find the node whose `phandle` (or legacy `linux,phandle`) property is
+    /// `phandle`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails or a phandle property is not a
+    /// single cell.
+    pub fn find_phandle(&self, phandle: u32) -> Result<Option<Node<'dt>>, Error> {
+        find_phandle(self.nodes()?, phandle)
+    }
+
+    /// Returns an iterator over the physical memory regions described by the
+    /// `reg` of every node with `device_type = "memory"`, in tree order.
+    ///
+    /// Each `reg` is decoded with the cell sizes of its parent, i.e. the root's
+    /// for the usual top-level `memory@...` nodes. Note that this does not take
+    /// the reservations from [`reserved_entries`](Self::reserved_entries) or
+    /// `/reserved-memory` into account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing the FDT fails. The iterator itself fails if
+    /// walking the tree fails or a `reg` uses an unsupported number of cells.
+    pub fn memory_regions(
+        &self,
+    ) -> Result<impl FallibleIterator<Item = RegEntry, Error = Error> + 'dt, Error> {
+        Ok(self
+            .nodes()?
+            .filter_map(|(_, node)| {
+                let is_memory = node
+                    .find_property("device_type")?
+                    .is_some_and(|prop| prop.as_str().is_ok_and(|ty| ty == "memory"));
+
+                if is_memory { node.reg() } else { Ok(None) }
+            })
+            .flatten())
+    }
+
+    /// Returns an iterator over the CPUs described by the `cpu` children of
+    /// `/cpus`, in tree order.
+    ///
+    /// Other children such as `cpu-map` are skipped. Disabled CPUs are
+    /// yielded too, check [`Node::is_enabled`] on [`Cpu::node`] to skip them.
+    /// Yields nothing if there is no `/cpus` node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails. The iterator itself fails if
+    /// walking the children of `/cpus` fails.
+    pub fn cpus(&self) -> Result<CpusIter<'dt>, Error> {
+        let children = match self.find_node("/cpus")? {
+            Some(cpus) => Some(cpus.children()?),
+            None => None,
+        };
+        Ok(CpusIter { children })
+    }
+
+    /// Returns the `/chosen` node, which carries the parameters the firmware
+    /// or bootloader passes to the OS.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails.
+    pub fn chosen(&self) -> Result<Option<Chosen<'dt>>, Error> {
+        Ok(self.find_node("/chosen")?.map(|node| Chosen { node }))
+    }
+
+    /// Resolves `alias` through the `/aliases` node, which maps short names
+    /// like `serial0` to full node paths.
+    ///
+    /// Returns `Ok(None)` if there is no `/aliases` node, it has no property
+    /// named `alias`, or the path it names does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails or the alias is not a valid
+    /// UTF-8 path rooted at `/`.
+    pub fn resolve_alias(&self, alias: &str) -> Result<Option<Node<'dt>>, Error> {
+        let Some(aliases) = self.find_node("/aliases")? else {
+            return Ok(None);
+        };
+        let Some(path) = aliases.find_property(alias)? else {
+            return Ok(None);
+        };
+        self.find_node(path.as_str()?)
+    }
+
+    /// The board's `model` property from the root node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the root node's properties fails or the
+    /// property is not a valid UTF-8 string.
+    pub fn model(&self) -> Result<Option<&'dt str>, Error> {
+        self.root
+            .find_property("model")?
+            .map(|prop| prop.as_str())
+            .transpose()
+    }
+
+    /// The board's `compatible` strings from the root node, most specific
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the root node's properties fails or the
+    /// property is not a valid UTF-8 string.
+    pub fn compatible(&self) -> Result<Option<StringList<'dt>>, Error> {
+        self.root
+            .find_property("compatible")?
+            .map(|prop| prop.as_strlist())
+            .transpose()
     }
 
     pub fn properties(&self) -> PropertiesIter<'dt> {
@@ -271,6 +442,196 @@ impl<'dt> Fdt<'dt> {
     }
 }
 
+/// Find the node among `nodes` whose `phandle` (or legacy `linux,phandle`)
+/// property is `phandle`.
+fn find_phandle<'dt>(nodes: NodesIter<'dt>, phandle: u32) -> Result<Option<Node<'dt>>, Error> {
+    nodes.map(|(_, node)| Ok(node)).find(|node| {
+        for name in ["phandle", "linux,phandle"] {
+            if let Some(prop) = node.find_property(name)? {
+                return Ok(prop.as_u32()? == phandle);
+            }
+        }
+        Ok(false)
+    })
+}
+
+/// The ancestors of `node` below the root, outermost first, as the first
+/// `len` entries of the returned array. Returns `None` if `node` is the root or
+/// not part of `fdt`.
+///
+/// # Errors
+///
+/// Returns [`Error::TooDeep`] if `node` has more than [`MAX_TRACKED_DEPTH`]
+/// ancestors below the root, since the chain would be incomplete.
+#[expect(
+    clippy::type_complexity,
+    reason = "only used internally, a dedicated type would not make it clearer"
+)]
+fn ancestors<'dt>(
+    fdt: &Fdt<'dt>,
+    node: &Node<'dt>,
+) -> Result<Option<([Option<Node<'dt>>; MAX_TRACKED_DEPTH], usize)>, Error> {
+    // `ancestors[d - 1]` is the most recently visited node at depth `d`, which
+    // is an ancestor of every node visited after it at a greater depth. The
+    // root itself is not yielded by `nodes`.
+    let mut ancestors: [Option<Node<'dt>>; MAX_TRACKED_DEPTH] = [const { None }; MAX_TRACKED_DEPTH];
+    let mut nodes = fdt.nodes()?;
+    while let Some((depth, n)) = nodes.next()? {
+        if ptr::eq(n.raw, node.raw) {
+            if depth - 1 > MAX_TRACKED_DEPTH {
+                return Err(Error::TooDeep);
+            }
+            return Ok(Some((ancestors, depth - 1)));
+        }
+
+        if let Some(slot) = ancestors.get_mut(depth - 1) {
+            *slot = Some(n);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Resolve the interrupt parent of `node`: the target of the nearest
+/// `interrupt-parent` on the node or its ancestors, or the node's parent in the
+/// tree if there is none.
+fn interrupt_parent<'dt>(fdt: &Fdt<'dt>, node: &Node<'dt>) -> Result<Option<Node<'dt>>, Error> {
+    let resolve = |prop: Property<'dt>| {
+        let phandle = prop.as_u32()?;
+        find_phandle(fdt.nodes()?, phandle)?.ok_or(Error::InvalidPhandle(phandle))
+    };
+
+    if let Some(prop) = node.find_property("interrupt-parent")? {
+        return resolve(prop).map(Some);
+    }
+
+    let Some((ancestors, len)) = ancestors(fdt, node)? else {
+        return Ok(None);
+    };
+    let ancestors = ancestors[..len].iter().rev().flatten().chain([&fdt.root]);
+    let parent = ancestors.clone().next().cloned();
+
+    for ancestor in ancestors {
+        if let Some(prop) = ancestor.find_property("interrupt-parent")? {
+            return resolve(prop).map(Some);
+        }
+    }
+
+    Ok(parent)
+}
+
+/// Translate `addr` through a single `ranges` property, see
+/// [`Node::translate_address`].
+fn translate_through(
+    ranges: &[u8],
+    addr: u64,
+    child_cells: CellSizes,
+    parent_address_cells: usize,
+) -> Result<Option<u64>, Error> {
+    // An empty `ranges` means the child and parent address spaces are identical.
+    if ranges.is_empty() {
+        return Ok(Some(addr));
+    }
+
+    let child_bytes = child_cells.address_cells * 4;
+    let parent_bytes = parent_address_cells * 4;
+    let entry_bytes = child_bytes + parent_bytes + child_cells.size_cells * 4;
+    if entry_bytes == 0 || !ranges.len().is_multiple_of(entry_bytes) {
+        return Err(Error::InvalidPropertyValue);
+    }
+
+    for entry in ranges.chunks_exact(entry_bytes) {
+        let (child_base, rest) = entry.split_at(child_bytes);
+        let (parent_base, size) = rest.split_at(parent_bytes);
+
+        let child_base = read_cells(child_base)?;
+        let parent_base = read_cells(parent_base)?;
+        let size = read_cells(size)?;
+
+        if let Some(offset) = addr.checked_sub(child_base)
+            && offset < size
+        {
+            return parent_base
+                .checked_add(offset)
+                .ok_or(Error::InvalidPropertyValue)
+                .map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Decode a one or two cell big endian number.
+fn read_cells(encoded: &[u8]) -> Result<u64, Error> {
+    match *encoded {
+        [a, b, c, d] => Ok(u64::from(u32::from_be_bytes([a, b, c, d]))),
+        [a, b, c, d, e, f, g, h] => Ok(u64::from_be_bytes([a, b, c, d, e, f, g, h])),
+        _ => Err(Error::InvalidCellSize),
+    }
+}
+
+/// The `#interrupt-cells` of the interrupt controller `controller`.
+///
+/// Zero cells are rejected, since an interrupt specifier without cells would never consume any
+/// of the `interrupts` property.
+fn interrupt_cells(controller: &Node<'_>) -> Result<usize, Error> {
+    let cells = controller
+        .find_property("#interrupt-cells")?
+        .ok_or(Error::InvalidCellSize)?
+        .as_u32()?;
+    let cells = usize::try_from(cells)?;
+
+    if cells == 0 || cells > MAX_INTERRUPT_CELLS {
+        return Err(Error::InvalidCellSize);
+    }
+    Ok(cells)
+}
+
+/// Advance `nodes` to the child matching the path segment `want` among the
+/// nodes at `depth`, leaving `nodes` positioned right after it so the next
+/// items are that child's descendants.
+fn find_child<'dt>(
+    nodes: &mut NodesIter<'dt>,
+    depth: usize,
+    want: &str,
+) -> Result<Option<Node<'dt>>, Error> {
+    // A segment without a unit address may match a node that has one, but we
+    // can only tell that the match is unique after looking at all siblings, so
+    // remember where to resume.
+    let mut candidate: Option<(NodesIter<'dt>, Node<'dt>)> = None;
+    let mut ambiguous = false;
+
+    while let Some((d, node)) = nodes.next()? {
+        if d < depth {
+            break;
+        }
+        if d > depth {
+            continue;
+        }
+
+        let name = node.name()?;
+        match want.split_once('@') {
+            Some((n, a)) if n == name.name && name.unit_address == Some(a) => {
+                return Ok(Some(node));
+            }
+            None if want == name.name && name.unit_address.is_none() => return Ok(Some(node)),
+            None if want == name.name => {
+                ambiguous |= candidate.is_some();
+                candidate = Some((nodes.clone(), node));
+            }
+            _ => {}
+        }
+    }
+
+    match candidate {
+        Some((resume, node)) if !ambiguous => {
+            *nodes = resume;
+            Ok(Some(node))
+        }
+        _ => Ok(None),
+    }
+}
+
 impl fmt::Debug for Node<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Node")
@@ -317,6 +678,23 @@ impl<'dt> Node<'dt> {
         self.properties().find(|p| Ok(p.name == name))
     }
 
+    /// Whether this node's device is usable, i.e. its `status` property is
+    /// absent, `"okay"` or the legacy `"ok"`.
+    ///
+    /// Any other status (`"disabled"`, `"reserved"`, `"fail"`, ...) means the
+    /// device should not be bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the node's properties fails or the
+    /// property is not a valid UTF-8 string.
+    pub fn is_enabled(&self) -> Result<bool, Error> {
+        let Some(status) = self.find_property("status")? else {
+            return Ok(true);
+        };
+        Ok(matches!(status.as_str()?, "okay" | "ok"))
+    }
+
     /// The `#address-cells` / `#size-cells` that govern this node's `reg`.
     ///
     /// Resolved from the nearest ancestor that declares them, falling back to the
@@ -326,6 +704,32 @@ impl<'dt> Node<'dt> {
         self.cell_sizes
     }
 
+    /// Returns an iterator over the immediate children of this node, in tree
+    /// order.
+    ///
+    /// Deeper descendants are skipped and iteration stops at the end of this
+    /// node, so siblings of the node are never yielded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if parsing the node's properties fails.
+    pub fn children(&self) -> Result<ChildrenIter<'dt>, Error> {
+        let mut parser = Parser::new(self.raw, self.strings, self.structs);
+
+        // Seed depth 0 with this node's child cells so the children inherit them.
+        let mut cells_stack = [CellSizes::default(); MAX_TRACKED_DEPTH];
+        cells_stack[0] = parser.child_cell_sizes(self.cell_sizes)?;
+
+        Ok(ChildrenIter {
+            nodes: NodesIter {
+                parser,
+                depth: 0,
+                cells_stack,
+            },
+            done: false,
+        })
+    }
+
     /// The node's `reg` decoded with its governing [`cell_sizes`](Self::cell_sizes).
     ///
     /// # Errors
@@ -336,6 +740,183 @@ impl<'dt> Node<'dt> {
             .find_property("reg")?
             .map(|reg| reg.as_regs(self.cell_sizes)))
     }
+
+    /// Translate `child_addr`, an address in the bus address space this node's
+    /// `reg` is expressed in, to a physical address by applying the `ranges` of
+    /// every ancestor bus in turn.
+    ///
+    /// Each `ranges` entry is decoded with the `#address-cells` / `#size-cells`
+    /// of the bus for the child side and its parent's `#address-cells` for the
+    /// parent side. An empty `ranges` maps addresses one to one. Returns
+    /// `Ok(None)` if an address is not covered by a bus' `ranges`, or a bus has
+    /// no `ranges` at all and so isn't memory mapped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails or a `ranges` property is
+    /// malformed. Only addresses and sizes of one or two cells are supported,
+    /// which excludes e.g. PCI buses. Nodes with more than 32 ancestors below
+    /// the root are rejected with [`Error::TooDeep`].
+    pub fn translate_address(&self, child_addr: u64, fdt: &Fdt<'dt>) -> Result<Option<u64>, Error> {
+        if ptr::eq(self.raw, fdt.root.raw) {
+            return Ok(Some(child_addr));
+        }
+        let Some((ancestors, len)) = ancestors(fdt, self)? else {
+            return Ok(None);
+        };
+
+        // Each bus maps the address space of its children, whose cell sizes are
+        // recorded on the child, into its own.
+        let mut addr = child_addr;
+        let mut child_cells = self.cell_sizes;
+        for bus in ancestors[..len].iter().rev().flatten() {
+            let Some(ranges) = bus.find_property("ranges")? else {
+                return Ok(None);
+            };
+            let Some(translated) =
+                translate_through(ranges.raw, addr, child_cells, bus.cell_sizes.address_cells)?
+            else {
+                return Ok(None);
+            };
+
+            addr = translated;
+            child_cells = bus.cell_sizes;
+        }
+
+        Ok(Some(addr))
+    }
+
+    /// The node's interrupt specifiers, decoded from `interrupts-extended` if
+    /// present and `interrupts` otherwise.
+    ///
+    /// For `interrupts`, every specifier belongs to the node's interrupt parent:
+    /// the target of the nearest `interrupt-parent` on this node or its
+    /// ancestors, or the node's parent in the tree if there is none. The length
+    /// of a specifier is given by its controller's `#interrupt-cells`.
+    /// `interrupt-map` nexus nodes are not followed, the controller reported is
+    /// the one named by the tree.
+    ///
+    /// Nodes without either property yield no specifiers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the FDT fails or the interrupt parent cannot be
+    /// resolved, e.g. because the node is nested too deeply to walk its
+    /// ancestors. The iterator itself fails if a specifier is truncated or
+    /// references an unknown controller.
+    pub fn interrupts(&self, fdt: &Fdt<'dt>) -> Result<Interrupts<'dt>, Error> {
+        if let Some(prop) = self.find_property("interrupts-extended")? {
+            return Ok(Interrupts {
+                encoded_array: prop.raw,
+                parent: None,
+                nodes: fdt.nodes()?,
+            });
+        }
+
+        let Some(prop) = self.find_property("interrupts")? else {
+            return Ok(Interrupts {
+                encoded_array: &[],
+                parent: None,
+                nodes: fdt.nodes()?,
+            });
+        };
+
+        let parent = interrupt_parent(fdt, self)?.ok_or(Error::InvalidPropertyValue)?;
+        let cells = interrupt_cells(&parent)?;
+
+        Ok(Interrupts {
+            encoded_array: prop.raw,
+            parent: Some((parent, cells)),
+            nodes: fdt.nodes()?,
+        })
+    }
+}
+
+/// A CPU node, returned by [`Fdt::cpus`].
+#[derive(Debug, Clone)]
+pub struct Cpu<'dt> {
+    node: Node<'dt>,
+}
+
+impl<'dt> Cpu<'dt> {
+    /// The underlying node, for properties without a typed accessor.
+    #[must_use]
+    pub fn node(&self) -> &Node<'dt> {
+        &self.node
+    }
+
+    /// The hardware ID of this CPU (the hart ID on RISC-V), read from the
+    /// first entry of its `reg`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the node's properties fails, the node has
+    /// no (or an empty) `reg` property, or `/cpus` has an `#address-cells`
+    /// other than one or two ([`Error::InvalidCellSize`]).
+    pub fn hart_id(&self) -> Result<usize, Error> {
+        let mut reg = self.node.reg()?.ok_or(Error::InvalidPropertyValue)?;
+        let entry = reg.next()?.ok_or(Error::InvalidPropertyValue)?;
+        Ok(entry.starting_address)
+    }
+
+    /// The CPU's `compatible` strings, most specific first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if walking the node's properties fails or the
+    /// property is not a valid UTF-8 string.
+    pub fn compatible(&self) -> Result<Option<StringList<'dt>>, Error> {
+        self.node
+            .find_property("compatible")?
+            .map(|prop| prop.as_strlist())
+            .transpose()
+    }
+}
+
+/// Typed accessors for the properties of the `/chosen` node, returned by
+/// [`Fdt::chosen`].
+///
+/// Properties that are missing or malformed are reported as `None`.
+#[derive(Debug, Clone)]
+pub struct Chosen<'dt> {
+    node: Node<'dt>,
+}
+
+impl<'dt> Chosen<'dt> {
+    /// The underlying node, for properties without a typed accessor.
+    #[must_use]
+    pub fn node(&self) -> &Node<'dt> {
+        &self.node
+    }
+
+    /// The kernel command line (`bootargs`).
+    #[must_use]
+    pub fn bootargs(&self) -> Option<&'dt str> {
+        self.str_prop("bootargs")
+    }
+
+    /// The path (or alias) of the console device (`stdout-path`), falling back
+    /// to the legacy `linux,stdout-path`.
+    ///
+    /// The value may carry a `:options` suffix, e.g. `serial0:115200n8`.
+    #[must_use]
+    pub fn stdout_path(&self) -> Option<&'dt str> {
+        self.str_prop("stdout-path")
+            .or_else(|| self.str_prop("linux,stdout-path"))
+    }
+
+    /// The physical `(start, end)` addresses of the initial ramdisk, read from
+    /// `linux,initrd-start` and `linux,initrd-end`.
+    #[must_use]
+    pub fn initrd(&self) -> Option<(u64, u64)> {
+        let start = self.node.find_property("linux,initrd-start").ok()??;
+        let end = self.node.find_property("linux,initrd-end").ok()??;
+        Some((start.as_u64().ok()?, end.as_u64().ok()?))
+    }
+
+    fn str_prop(&self, name: &str) -> Option<&'dt str> {
+        self.node.find_property(name).ok()??.as_str().ok()
+    }
 }
 
 impl<'dt> Property<'dt> {
@@ -439,6 +1020,7 @@ impl<'dt> Iterator for StringList<'dt> {
     }
 }
 
+#[derive(Clone)]
 pub struct NodesIter<'dt> {
     pub(crate) parser: Parser<'dt>,
     pub(crate) depth: usize,
@@ -491,6 +1073,64 @@ impl<'dt> FallibleIterator for NodesIter<'dt> {
     }
 }
 
+/// An iterator over the immediate children of a node, see [`Node::children`].
+#[derive(Clone)]
+pub struct ChildrenIter<'dt> {
+    nodes: NodesIter<'dt>,
+    done: bool,
+}
+impl<'dt> FallibleIterator for ChildrenIter<'dt> {
+    type Item = Node<'dt>;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        while !self.done {
+            // Consume `END_NODE`s ourselves, `NodesIter` would happily step over
+            // the one closing the parent and continue with its siblings.
+            while let Ok(BigEndianToken::END_NODE) = self.nodes.parser.peek_token() {
+                if self.nodes.depth == 0 {
+                    self.done = true;
+                    return Ok(None);
+                }
+                let _ = self.nodes.parser.advance_token();
+                self.nodes.depth -= 1;
+            }
+
+            match self.nodes.next()? {
+                Some((1, node)) => return Ok(Some(node)),
+                Some(_) => {}
+                None => self.done = true,
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// An iterator over the CPUs of a devicetree, see [`Fdt::cpus`].
+#[derive(Clone)]
+pub struct CpusIter<'dt> {
+    children: Option<ChildrenIter<'dt>>,
+}
+impl<'dt> FallibleIterator for CpusIter<'dt> {
+    type Item = Cpu<'dt>;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(children) = &mut self.children else {
+            return Ok(None);
+        };
+
+        while let Some(node) = children.next()? {
+            if node.name()?.name == "cpu" {
+                return Ok(Some(Cpu { node }));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
 pub struct PropertiesIter<'dt> {
     pub(crate) parser: Parser<'dt>,
 }
@@ -610,17 +1250,13 @@ impl FallibleIterator for Regs<'_> {
 
         self.encoded_array = &self.encoded_array[address_bytes + size_bytes..];
 
-        let starting_address = match self.cell_sizes.address_cells {
-            1 => usize::try_from(u32::from_be_bytes(encoded_address.try_into()?))?,
-            2 => usize::try_from(u64::from_be_bytes(encoded_address.try_into()?))?,
-            _ => unreachable!(),
-        };
+        // The cell counts come straight from the blob, so anything but one or two cells (or zero
+        // size cells) is malformed input, not a bug.
+        let starting_address = usize::try_from(read_cells(encoded_address)?)?;
 
         let size = match self.cell_sizes.size_cells {
             0 => None,
-            1 => usize::try_from(u32::from_be_bytes(encoded_len.try_into()?)).ok(),
-            2 => usize::try_from(u64::from_be_bytes(encoded_len.try_into()?)).ok(),
-            _ => unreachable!(),
+            _ => usize::try_from(read_cells(encoded_len)?).ok(),
         };
 
         Ok(Some(RegEntry {
@@ -629,3 +1265,1153 @@ impl FallibleIterator for Regs<'_> {
         }))
     }
 }
+
+/// Iterator over the interrupt specifiers of a node, returned by
+/// [`Node::interrupts`].
+pub struct Interrupts<'dt> {
+    encoded_array: &'dt [u8],
+    /// The controller and its `#interrupt-cells` shared by all specifiers, or
+    /// `None` for `interrupts-extended` where each specifier starts with the
+    /// phandle of its controller.
+    parent: Option<(Node<'dt>, usize)>,
+    /// Used to resolve the phandles of `interrupts-extended`.
+    nodes: NodesIter<'dt>,
+}
+
+/// A single decoded interrupt specifier.
+#[derive(Debug, Clone)]
+pub struct Interrupt<'dt> {
+    parent: Node<'dt>,
+    cells: [u32; MAX_INTERRUPT_CELLS],
+    len: usize,
+}
+
+impl<'dt> Interrupt<'dt> {
+    /// The interrupt controller this specifier is interpreted by.
+    #[must_use]
+    pub fn parent(&self) -> &Node<'dt> {
+        &self.parent
+    }
+
+    /// The specifier cells, as many as the controller's `#interrupt-cells`.
+    #[must_use]
+    pub fn cells(&self) -> &[u32] {
+        &self.cells[..self.len]
+    }
+}
+
+impl<'dt> FallibleIterator for Interrupts<'dt> {
+    type Item = Interrupt<'dt>;
+    type Error = Error;
+
+    fn next(&mut self) -> Result<Option<Self::Item>, Self::Error> {
+        if self.encoded_array.is_empty() {
+            return Ok(None);
+        }
+
+        let (parent, len) = if let Some((parent, len)) = &self.parent {
+            (parent.clone(), *len)
+        } else {
+            let (phandle, rest) = self
+                .encoded_array
+                .split_first_chunk::<4>()
+                .ok_or(Error::InvalidPropertyValue)?;
+            let phandle = u32::from_be_bytes(*phandle);
+            self.encoded_array = rest;
+
+            let parent =
+                find_phandle(self.nodes.clone(), phandle)?.ok_or(Error::InvalidPhandle(phandle))?;
+            let len = interrupt_cells(&parent)?;
+            (parent, len)
+        };
+
+        let Some(encoded) = self.encoded_array.get(..len * 4) else {
+            return Err(Error::InvalidPropertyValue);
+        };
+        self.encoded_array = &self.encoded_array[len * 4..];
+
+        let mut cells = [0; MAX_INTERRUPT_CELLS];
+        for (cell, encoded) in cells.iter_mut().zip(encoded.chunks_exact(4)) {
+            *cell = u32::from_be_bytes(encoded.try_into()?);
+        }
+
+        Ok(Some(Interrupt { parent, cells, len }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Copies a blob written by `FdtWriter` into a 4-byte aligned buffer, as `Fdt::new` requires.
+    fn to_words(bytes: &[u8]) -> Vec<u32> {
+        bytes
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_ne_bytes(word)
+            })
+            .collect()
+    }
+
+    fn blob() -> Vec<u32> {
+        to_words(
+            &FdtWriter::new()
+                .begin_node("")
+                .property("model", b"test\0")
+                .property("compatible", b"vendor,board\0vendor,soc\0")
+                .begin_node("chosen")
+                .property("bootargs", b"console=ttyS0 quiet\0")
+                .property("stdout-path", b"/soc/uart@10000000:115200n8\0")
+                .property("linux,initrd-start", &cells(&[0x8200_0000]))
+                .property("linux,initrd-end", &cells(&[0x1, 0x0]))
+                .end_node()
+                .begin_node("cpus")
+                .begin_node("cpu@0")
+                .property("linux,phandle", &2_u32.to_be_bytes())
+                .end_node()
+                .begin_node("cpu@1")
+                .end_node()
+                .end_node()
+                .begin_node("soc")
+                .begin_node("uart@10000000")
+                .property("compatible", b"ns16550a\0")
+                .property("reg", &[0; 16])
+                .property("interrupt-parent", &1_u32.to_be_bytes())
+                .end_node()
+                .begin_node("plic@c000000")
+                .property("phandle", &1_u32.to_be_bytes())
+                .end_node()
+                .begin_node("serial@2000")
+                .property("compatible", b"vendor,uart-v2\0ns16550a\0")
+                .end_node()
+                .begin_node("serial")
+                .property("compatible", b"vendor,other\0")
+                .end_node()
+                .end_node()
+                .begin_node("memory@80000000")
+                .property("device_type", b"memory\0")
+                .property("reg", &cells(&[0x0, 0x8000_0000, 0x4000_0000]))
+                .end_node()
+                .begin_node("memory@100000000")
+                .property("device_type", b"memory\0")
+                .property(
+                    "reg",
+                    &cells(&[0x1, 0x0, 0x1000_0000, 0x1, 0x2000_0000, 0x1000]),
+                )
+                .end_node()
+                .end_node()
+                .finish(),
+        )
+    }
+
+    fn find(fdt: &Fdt<'_>, path: &str) -> Option<String> {
+        let node = fdt.find_node(path).unwrap()?;
+        let name = node.name().unwrap();
+        Some(match name.unit_address {
+            Some(addr) => format!("{}@{addr}", name.name),
+            None => name.name.to_string(),
+        })
+    }
+
+    #[test]
+    fn find_node_root() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let root = fdt.find_node("/").unwrap().unwrap();
+        assert_eq!(root.name().unwrap().name, "/");
+        assert!(root.find_property("model").unwrap().is_some());
+    }
+
+    #[test]
+    fn find_node_hits() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(find(&fdt, "/chosen").as_deref(), Some("chosen"));
+        assert_eq!(find(&fdt, "/cpus/cpu@1").as_deref(), Some("cpu@1"));
+        assert_eq!(
+            find(&fdt, "/soc/uart@10000000").as_deref(),
+            Some("uart@10000000")
+        );
+        assert_eq!(
+            find(&fdt, "/memory@80000000/").as_deref(),
+            Some("memory@80000000")
+        );
+
+        let uart = fdt.find_node("/soc/uart@10000000").unwrap().unwrap();
+        assert!(uart.find_property("reg").unwrap().is_some());
+    }
+
+    #[test]
+    fn find_node_without_unit_address() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        // unique by name
+        assert_eq!(find(&fdt, "/soc/uart").as_deref(), Some("uart@10000000"));
+        // an exact name match wins over a node with a unit address
+        assert_eq!(find(&fdt, "/soc/serial").as_deref(), Some("serial"));
+        // ambiguous
+        assert_eq!(find(&fdt, "/cpus/cpu"), None);
+        assert_eq!(find(&fdt, "/memory"), None);
+    }
+
+    #[test]
+    fn find_node_misses() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(find(&fdt, "/nope"), None);
+        assert_eq!(find(&fdt, "/soc/uart@0"), None);
+        assert_eq!(find(&fdt, "/uart"), None);
+        assert_eq!(find(&fdt, "/cpus/cpu@0/child"), None);
+        assert_eq!(find(&fdt, "/chosen/cpu@0"), None);
+        assert!(matches!(fdt.find_node("chosen"), Err(Error::InvalidPath)));
+        assert!(matches!(fdt.find_node(""), Err(Error::InvalidPath)));
+    }
+
+    fn compatible(fdt: &Fdt<'_>, with: &[&str]) -> Vec<String> {
+        fdt.find_compatible(with)
+            .unwrap()
+            .map(|node| {
+                let name = node.name()?;
+                Ok(match name.unit_address {
+                    Some(addr) => format!("{}@{addr}", name.name),
+                    None => name.name.to_string(),
+                })
+            })
+            .collect()
+            .unwrap()
+    }
+
+    #[test]
+    fn find_compatible_first_entry() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(compatible(&fdt, &["vendor,uart-v2"]), ["serial@2000"]);
+        assert_eq!(compatible(&fdt, &["vendor,other"]), ["serial"]);
+    }
+
+    #[test]
+    fn find_compatible_later_entry() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(
+            compatible(&fdt, &["ns16550a"]),
+            ["uart@10000000", "serial@2000"]
+        );
+        assert_eq!(
+            compatible(&fdt, &["nope", "vendor,other", "ns16550a"]),
+            ["uart@10000000", "serial@2000", "serial"]
+        );
+    }
+
+    #[test]
+    fn find_compatible_skips_non_matching() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert!(compatible(&fdt, &["nope"]).is_empty());
+        assert!(compatible(&fdt, &[]).is_empty());
+        // partial matches don't count
+        assert!(compatible(&fdt, &["ns16550"]).is_empty());
+    }
+
+    /// Big-endian encoding of `cells`, e.g. for `reg` or `#address-cells`.
+    fn cells(cells: &[u32]) -> Vec<u8> {
+        cells.iter().flat_map(|cell| cell.to_be_bytes()).collect()
+    }
+
+    fn regs(fdt: &Fdt<'_>, path: &str) -> Vec<(usize, Option<usize>)> {
+        fdt.find_node(path)
+            .unwrap()
+            .unwrap()
+            .reg()
+            .unwrap()
+            .unwrap()
+            .map(|reg| Ok((reg.starting_address, reg.size)))
+            .collect()
+            .unwrap()
+    }
+
+    fn children_blob() -> Vec<u32> {
+        to_words(
+            &FdtWriter::new()
+                .begin_node("")
+                .begin_node("cpus")
+                .property("#address-cells", &cells(&[1]))
+                .property("#size-cells", &cells(&[0]))
+                .begin_node("cpu@0")
+                .property("reg", &cells(&[0]))
+                .begin_node("interrupt-controller")
+                .end_node()
+                .end_node()
+                .begin_node("cpu@1")
+                .property("reg", &cells(&[1]))
+                .begin_node("interrupt-controller")
+                .end_node()
+                .end_node()
+                .begin_node("cpu-map")
+                .end_node()
+                .end_node()
+                .begin_node("memory@80000000")
+                .end_node()
+                .end_node()
+                .finish(),
+        )
+    }
+
+    fn child_names(node: &Node<'_>) -> Vec<String> {
+        node.children()
+            .unwrap()
+            .map(|child| {
+                let name = child.name()?;
+                Ok(match name.unit_address {
+                    Some(addr) => format!("{}@{addr}", name.name),
+                    None => name.name.to_string(),
+                })
+            })
+            .collect()
+            .unwrap()
+    }
+
+    #[test]
+    fn children() {
+        let blob = children_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let cpus = fdt.find_node("/cpus").unwrap().unwrap();
+        assert_eq!(child_names(&cpus), ["cpu@0", "cpu@1", "cpu-map"]);
+
+        // children inherit the cell sizes declared by their parent
+        let cpu = cpus.children().unwrap().next().unwrap().unwrap();
+        assert_eq!(cpu.cell_sizes().address_cells, 1);
+        assert_eq!(cpu.cell_sizes().size_cells, 0);
+
+        let root = fdt.find_node("/").unwrap().unwrap();
+        assert_eq!(child_names(&root), ["cpus", "memory@80000000"]);
+    }
+
+    #[test]
+    fn children_of_leaf() {
+        let blob = children_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let cpu_map = fdt.find_node("/cpus/cpu-map").unwrap().unwrap();
+        assert!(child_names(&cpu_map).is_empty());
+        let memory = fdt.find_node("/memory@80000000").unwrap().unwrap();
+        assert!(child_names(&memory).is_empty());
+    }
+
+    fn cells_blob() -> Vec<u32> {
+        to_words(
+            &FdtWriter::new()
+                .begin_node("")
+                // uses the spec defaults: 2 address cells, 1 size cell
+                .begin_node("memory@80000000")
+                .property("reg", &cells(&[0x0, 0x8000_0000, 0x1000, 0x1, 0x0, 0x2000]))
+                .end_node()
+                .begin_node("soc")
+                .property("#address-cells", &cells(&[1]))
+                .property("#size-cells", &cells(&[2]))
+                .property("reg", &cells(&[0x0, 0x0, 0x10]))
+                .begin_node("uart@10000000")
+                .property(
+                    "reg",
+                    &cells(&[0x1000_0000, 0x0, 0x100, 0x1000_1000, 0x1, 0x0]),
+                )
+                .end_node()
+                .begin_node("bus")
+                .property("#size-cells", &cells(&[0]))
+                .property("reg", &cells(&[0x2000_0000, 0x0, 0x1000]))
+                .begin_node("dev@5")
+                .property("reg", &cells(&[0x5, 0x6]))
+                .end_node()
+                .end_node()
+                .end_node()
+                .end_node()
+                .finish(),
+        )
+    }
+
+    #[test]
+    fn reg_default_cell_sizes() {
+        let blob = cells_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let memory = fdt.find_node("/memory@80000000").unwrap().unwrap();
+        assert_eq!(memory.cell_sizes().address_cells, 2);
+        assert_eq!(memory.cell_sizes().size_cells, 1);
+        assert_eq!(
+            regs(&fdt, "/memory@80000000"),
+            [(0x8000_0000, Some(0x1000)), (0x1_0000_0000, Some(0x2000))]
+        );
+
+        // The node's own declarations only apply to its children.
+        assert_eq!(regs(&fdt, "/soc"), [(0x0, Some(0x10))]);
+    }
+
+    #[test]
+    fn reg_overridden_cell_sizes() {
+        let blob = cells_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(
+            regs(&fdt, "/soc/uart@10000000"),
+            [
+                (0x1000_0000, Some(0x100)),
+                (0x1000_1000, Some(0x1_0000_0000))
+            ]
+        );
+
+        // `bus` inherits `#address-cells` from `soc` but overrides `#size-cells`.
+        assert_eq!(regs(&fdt, "/soc/bus"), [(0x2000_0000, Some(0x1000))]);
+        assert_eq!(regs(&fdt, "/soc/bus/dev@5"), [(0x5, None), (0x6, None)]);
+    }
+
+    #[test]
+    fn find_phandle() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let uart = fdt.find_node("/soc/uart").unwrap().unwrap();
+        let parent = uart
+            .find_property("interrupt-parent")
+            .unwrap()
+            .unwrap()
+            .as_u32()
+            .unwrap();
+        let plic = fdt.find_phandle(parent).unwrap().unwrap();
+        assert_eq!(plic.name().unwrap().name, "plic");
+
+        let cpu = fdt.find_phandle(2).unwrap().unwrap();
+        assert_eq!(cpu.name().unwrap().unit_address, Some("0"));
+
+        assert!(fdt.find_phandle(3).unwrap().is_none());
+    }
+
+    fn interrupts_blob() -> Vec<u32> {
+        to_words(
+            &FdtWriter::new()
+                .begin_node("")
+                .property("interrupt-parent", &cells(&[1]))
+                .begin_node("soc")
+                .begin_node("plic@c000000")
+                .property("phandle", &cells(&[1]))
+                .property("interrupt-controller", &[])
+                .property("#interrupt-cells", &cells(&[1]))
+                .end_node()
+                .begin_node("gic@8000000")
+                .property("phandle", &cells(&[2]))
+                .property("interrupt-controller", &[])
+                .property("#interrupt-cells", &cells(&[3]))
+                .end_node()
+                .begin_node("uart@10000000")
+                .property("interrupts", &cells(&[10, 11]))
+                .end_node()
+                .begin_node("timer@9000000")
+                .property("interrupt-parent", &cells(&[2]))
+                .property("interrupts", &cells(&[0, 33, 4, 1, 27, 8]))
+                .end_node()
+                .begin_node("mailbox@4000000")
+                .property("interrupt-parent", &cells(&[2]))
+                .property("interrupts-extended", &cells(&[1, 5, 2, 0, 40, 1]))
+                .end_node()
+                .begin_node("rtc@101000")
+                .property("interrupt-parent", &cells(&[3]))
+                .property("interrupts", &cells(&[12]))
+                .end_node()
+                .begin_node("rng@102000")
+                .end_node()
+                .end_node()
+                .end_node()
+                .finish(),
+        )
+    }
+
+    fn interrupts(fdt: &Fdt<'_>, path: &str) -> Result<Vec<(String, Vec<u32>)>, Error> {
+        fdt.find_node(path)
+            .unwrap()
+            .unwrap()
+            .interrupts(fdt)?
+            .map(|irq| Ok((irq.parent().name()?.name.to_string(), irq.cells().to_vec())))
+            .collect()
+    }
+
+    #[test]
+    fn interrupts_inherited_parent() {
+        let blob = interrupts_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(
+            interrupts(&fdt, "/soc/uart").unwrap(),
+            [
+                ("plic".to_string(), vec![10]),
+                ("plic".to_string(), vec![11])
+            ]
+        );
+    }
+
+    #[test]
+    fn interrupts_explicit_parent() {
+        let blob = interrupts_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(
+            interrupts(&fdt, "/soc/timer").unwrap(),
+            [
+                ("gic".to_string(), vec![0, 33, 4]),
+                ("gic".to_string(), vec![1, 27, 8])
+            ]
+        );
+    }
+
+    #[test]
+    fn interrupts_extended() {
+        let blob = interrupts_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        // takes precedence over `interrupt-parent`
+        assert_eq!(
+            interrupts(&fdt, "/soc/mailbox").unwrap(),
+            [
+                ("plic".to_string(), vec![5]),
+                ("gic".to_string(), vec![0, 40, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn interrupts_missing() {
+        let blob = interrupts_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert!(interrupts(&fdt, "/soc/rng").unwrap().is_empty());
+        assert!(matches!(
+            interrupts(&fdt, "/soc/rtc"),
+            Err(Error::InvalidPhandle(3))
+        ));
+    }
+
+    #[test]
+    fn interrupts_zero_cells() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("intc")
+            .property_u32("phandle", 1)
+            .property("interrupt-controller", &[])
+            .property_u32("#interrupt-cells", 0)
+            .end_node()
+            .begin_node("dev")
+            .property_u32("interrupt-parent", 1)
+            .property("interrupts", &cells(&[1, 2]))
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        // must not yield empty interrupts forever
+        assert!(matches!(
+            interrupts(&fdt, "/dev"),
+            Err(Error::InvalidCellSize)
+        ));
+    }
+
+    fn ranges_blob() -> Vec<u32> {
+        to_words(
+            &FdtWriter::new()
+                .begin_node("")
+                .property("#address-cells", &cells(&[2]))
+                .property("#size-cells", &cells(&[2]))
+                .begin_node("soc")
+                .property("#address-cells", &cells(&[1]))
+                .property("#size-cells", &cells(&[1]))
+                // child 0x0..0x1000_0000 -> parent 0x4000_0000
+                .property("ranges", &cells(&[0x0, 0x0, 0x4000_0000, 0x1000_0000]))
+                .begin_node("uart@1000")
+                .property("reg", &cells(&[0x1000, 0x100]))
+                .end_node()
+                .end_node()
+                .begin_node("i2c")
+                .property("#address-cells", &cells(&[1]))
+                .property("#size-cells", &cells(&[0]))
+                .begin_node("sensor@48")
+                .property("reg", &cells(&[0x48]))
+                .end_node()
+                .end_node()
+                .end_node()
+                .finish(),
+        )
+    }
+
+    #[test]
+    fn translate_address() {
+        let blob = ranges_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let uart = fdt.find_node("/soc/uart").unwrap().unwrap();
+        assert_eq!(
+            uart.translate_address(0x1000, &fdt).unwrap(),
+            Some(0x4000_1000)
+        );
+        assert_eq!(
+            uart.translate_address(0xfff_ffff, &fdt).unwrap(),
+            Some(0x4fff_ffff)
+        );
+        assert_eq!(uart.translate_address(0x1000_0000, &fdt).unwrap(), None);
+
+        // direct children of the root are already physical
+        let soc = fdt.find_node("/soc").unwrap().unwrap();
+        assert_eq!(
+            soc.translate_address(0x1000_0000, &fdt).unwrap(),
+            Some(0x1000_0000)
+        );
+    }
+
+    #[test]
+    fn translate_address_too_deep() {
+        let mut writer = FdtWriter::new();
+        writer.begin_node("");
+        for _ in 0..MAX_TRACKED_DEPTH + 2 {
+            writer
+                .begin_node("bus")
+                .property("ranges", &[])
+                .property_u32("interrupts", 1);
+        }
+        for _ in 0..MAX_TRACKED_DEPTH + 3 {
+            writer.end_node();
+        }
+        let written = writer.finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        let mut nodes = fdt.nodes().unwrap();
+        let mut by_depth = Vec::new();
+        while let Some((_, node)) = nodes.next().unwrap() {
+            by_depth.push(node);
+        }
+
+        // every ancestor is still tracked
+        let deepest_tracked = &by_depth[MAX_TRACKED_DEPTH];
+        assert_eq!(
+            deepest_tracked.translate_address(0x1000, &fdt).unwrap(),
+            Some(0x1000)
+        );
+
+        // one level deeper, the outermost bus would be missing from the chain
+        let too_deep = &by_depth[MAX_TRACKED_DEPTH + 1];
+        assert!(matches!(
+            too_deep.translate_address(0x1000, &fdt),
+            Err(Error::TooDeep)
+        ));
+        assert!(matches!(too_deep.interrupts(&fdt), Err(Error::TooDeep)));
+    }
+
+    #[test]
+    fn translate_address_without_ranges() {
+        let blob = ranges_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        // `i2c` has no `ranges`, so its children are not memory mapped
+        let sensor = fdt.find_node("/i2c/sensor").unwrap().unwrap();
+        assert_eq!(sensor.translate_address(0x48, &fdt).unwrap(), None);
+    }
+
+    #[test]
+    fn model_and_compatible() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(fdt.model().unwrap(), Some("test"));
+        assert_eq!(
+            fdt.compatible().unwrap().unwrap().collect::<Vec<_>>(),
+            ["vendor,board", "vendor,soc"]
+        );
+    }
+
+    #[test]
+    fn model_and_compatible_missing() {
+        let blob = cells_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert_eq!(fdt.model().unwrap(), None);
+        assert!(fdt.compatible().unwrap().is_none());
+    }
+
+    #[test]
+    fn chosen() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let chosen = fdt.chosen().unwrap().unwrap();
+        assert_eq!(chosen.bootargs(), Some("console=ttyS0 quiet"));
+        assert_eq!(chosen.stdout_path(), Some("/soc/uart@10000000:115200n8"));
+        // one and two cell encodings
+        assert_eq!(chosen.initrd(), Some((0x8200_0000, 0x1_0000_0000)));
+    }
+
+    #[test]
+    fn chosen_missing() {
+        let blob = cells_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert!(fdt.chosen().unwrap().is_none());
+    }
+
+    #[test]
+    fn chosen_legacy_stdout_path() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("chosen")
+            .property("linux,stdout-path", b"serial0\0")
+            .property("linux,initrd-start", &cells(&[0x8200_0000]))
+            .end_node()
+            .end_node()
+            .finish();
+        let blob = to_words(&written);
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let chosen = fdt.chosen().unwrap().unwrap();
+        assert_eq!(chosen.stdout_path(), Some("serial0"));
+        assert_eq!(chosen.bootargs(), None);
+        assert_eq!(chosen.initrd(), None);
+    }
+
+    #[test]
+    fn memory_regions() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let regions: Vec<_> = fdt
+            .memory_regions()
+            .unwrap()
+            .map(|reg| Ok((reg.starting_address, reg.size)))
+            .collect()
+            .unwrap();
+        assert_eq!(
+            regions,
+            [
+                (0x8000_0000, Some(0x4000_0000)),
+                (0x1_0000_0000, Some(0x1000_0000)),
+                (0x1_2000_0000, Some(0x1000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn memory_regions_requires_device_type() {
+        // `cells_blob` has a `memory@80000000` node without `device_type`
+        let blob = cells_blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        assert!(fdt.memory_regions().unwrap().next().unwrap().is_none());
+    }
+
+    #[test]
+    fn memory_regions_unsupported_cells() {
+        for address_cells in [0, 3] {
+            let written = FdtWriter::new()
+                .begin_node("")
+                .property_u32("#address-cells", address_cells)
+                .property_u32("#size-cells", 1)
+                .begin_node("memory@0")
+                .property_str("device_type", "memory")
+                .property("reg", &cells(&[0x0, 0x8000_0000, 0x0, 0x1000]))
+                .end_node()
+                .end_node()
+                .finish();
+            let words = to_words(&written);
+            let fdt = Fdt::new(&words).unwrap();
+
+            let regions: Result<Vec<_>, _> = fdt.memory_regions().unwrap().collect();
+            assert!(matches!(regions, Err(Error::InvalidCellSize)));
+        }
+    }
+
+    /// Word indices of header fields, for corrupting test blobs.
+    const TOTAL_SIZE: usize = 1;
+    const STRUCTS_OFFSET: usize = 2;
+    const STRINGS_OFFSET: usize = 3;
+    const RESERVE_OFFSET: usize = 4;
+    const VERSION: usize = 5;
+    const LAST_COMPATIBLE_VERSION: usize = 6;
+    const STRINGS_SIZE: usize = 8;
+    const STRUCTS_SIZE: usize = 9;
+
+    fn corrupt(field: usize, f: impl FnOnce(u32) -> u32) -> Vec<u32> {
+        let mut blob = blob();
+        blob[field] = f(u32::from_be(blob[field])).to_be();
+        blob
+    }
+
+    #[test]
+    fn new_truncated() {
+        let blob = blob();
+
+        assert!(matches!(
+            Fdt::new(&blob[..blob.len() - 1]),
+            Err(Error::UnexpectedEof)
+        ));
+        assert!(matches!(Fdt::new(&blob[..5]), Err(Error::UnexpectedEof)));
+    }
+
+    #[test]
+    fn new_blocks_past_total_size() {
+        for blob in [
+            corrupt(STRUCTS_SIZE, |size| size + 0x1000),
+            corrupt(STRUCTS_OFFSET, |offset| offset + 0x1000),
+            corrupt(STRINGS_SIZE, |size| size + 4),
+            corrupt(STRINGS_OFFSET, |offset| offset + 0x1000),
+            corrupt(STRINGS_SIZE, |_| u32::MAX),
+            corrupt(RESERVE_OFFSET, |_| u32::MAX - 7),
+            corrupt(TOTAL_SIZE, |size| size - 4),
+        ] {
+            assert!(matches!(Fdt::new(&blob), Err(Error::MalformedHeader)));
+        }
+    }
+
+    #[test]
+    fn new_blocks_overlapping_header() {
+        for blob in [
+            corrupt(STRUCTS_OFFSET, |_| 0),
+            corrupt(STRINGS_OFFSET, |_| 4),
+            corrupt(RESERVE_OFFSET, |_| 8),
+            corrupt(TOTAL_SIZE, |_| 16),
+        ] {
+            assert!(matches!(Fdt::new(&blob), Err(Error::MalformedHeader)));
+        }
+    }
+
+    #[test]
+    fn new_misaligned_structs() {
+        let blob = corrupt(STRUCTS_OFFSET, |offset| offset + 2);
+        assert!(matches!(Fdt::new(&blob), Err(Error::MalformedHeader)));
+    }
+
+    #[test]
+    fn new_unsupported_version() {
+        for blob in [
+            corrupt(VERSION, |_| 16),
+            corrupt(LAST_COMPATIBLE_VERSION, |_| 18),
+        ] {
+            assert!(matches!(Fdt::new(&blob), Err(Error::UnsupportedVersion(_))));
+        }
+
+        // newer, but backwards compatible
+        let blob = corrupt(VERSION, |_| 18);
+        assert!(Fdt::new(&blob).is_ok());
+    }
+
+    /// Writes `fdt` back out, replacing the value of every property for which `replace` returns
+    /// `Some`.
+    fn rewrite(
+        fdt: &Fdt<'_>,
+        replace: impl Fn(&Node<'_>, &Property<'_>) -> Option<Vec<u8>>,
+    ) -> Vec<u8> {
+        let mut writer = FdtWriter::new();
+        writer.boot_cpuid(fdt.boot_cpuid());
+
+        let write_node = |writer: &mut FdtWriter, node: &Node<'_>| {
+            writer.begin_node(node.name.to_str().unwrap());
+            let mut props = node.properties();
+            while let Some(prop) = props.next().unwrap() {
+                match replace(node, &prop) {
+                    Some(value) => writer.property(prop.name, &value),
+                    None => writer.property(prop.name, prop.raw),
+                };
+            }
+        };
+
+        write_node(&mut writer, &fdt.root);
+        let mut open = 1;
+        let mut nodes = fdt.nodes().unwrap();
+        while let Some((depth, node)) = nodes.next().unwrap() {
+            for _ in depth..open {
+                writer.end_node();
+            }
+            write_node(&mut writer, &node);
+            open = depth + 1;
+        }
+        for _ in 0..open {
+            writer.end_node();
+        }
+
+        writer.finish()
+    }
+
+    /// Flattens the tree into `(depth, name, properties)` in tree order.
+    #[expect(clippy::type_complexity, reason = "only used for comparisons in tests")]
+    fn structure(fdt: &Fdt<'_>) -> Vec<(usize, String, Vec<(String, Vec<u8>)>)> {
+        let props = |node: &Node<'_>| {
+            node.properties()
+                .map(|prop| Ok((prop.name.to_string(), prop.raw.to_vec())))
+                .collect()
+                .unwrap()
+        };
+
+        let mut out = vec![(0, String::new(), props(&fdt.root))];
+        let mut nodes = fdt.nodes().unwrap();
+        while let Some((depth, node)) = nodes.next().unwrap() {
+            let name = node.name.to_str().unwrap().to_string();
+            out.push((depth, name, props(&node)));
+        }
+        out
+    }
+
+    /// The memory reservation block usually precedes the structure block and extends to the end
+    /// of the blob at most. `Fdt::new` used to compute its end relative to the structure block
+    /// instead, running past the end of every blob that isn't followed by enough slack.
+    #[test]
+    fn new_without_trailing_slack() {
+        let written = FdtWriter::new().begin_node("").end_node().finish();
+        let words = to_words(&written);
+        assert_eq!(words.len(), written.len().div_ceil(4));
+
+        let fdt = Fdt::new(&words).unwrap();
+        assert_eq!(fdt.total_size(), written.len());
+        assert!(fdt.header.memory_reserve_map_offset < fdt.header.structs_offset);
+        assert!(fdt.reserved_entries().next().unwrap().is_none());
+    }
+
+    #[test]
+    fn writer_round_trip() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let bytes = rewrite(&fdt, |_, _| None);
+        let written = to_words(&bytes);
+        let reparsed = Fdt::new(&written).unwrap();
+
+        assert_eq!(structure(&reparsed), structure(&fdt));
+        assert_eq!(reparsed.boot_cpuid(), fdt.boot_cpuid());
+        assert_eq!(reparsed.total_size(), bytes.len());
+        assert!(reparsed.reserved_entries().next().unwrap().is_none());
+
+        // `blob()` is built with `FdtWriter` as well, so rewriting it unchanged is byte-for-byte
+        // identical
+        assert_eq!(bytes, &fdt.as_slice()[..fdt.total_size()]);
+    }
+
+    #[test]
+    fn writer_override_property() {
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+
+        let written = to_words(&rewrite(&fdt, |node, prop| {
+            (node.name().unwrap().name == "chosen" && prop.name == "bootargs")
+                .then(|| b"console=hvc0\0".to_vec())
+        }));
+        let reparsed = Fdt::new(&written).unwrap();
+
+        let chosen = reparsed.chosen().unwrap().unwrap();
+        assert_eq!(chosen.bootargs(), Some("console=hvc0"));
+        // everything else is untouched
+        assert_eq!(
+            chosen.stdout_path(),
+            fdt.chosen().unwrap().unwrap().stdout_path()
+        );
+        assert_eq!(reparsed.model().unwrap(), Some("test"));
+    }
+
+    #[test]
+    fn writer_minimal() {
+        let blob = FdtWriter::new()
+            .begin_node("")
+            .property_u32("#address-cells", 1)
+            .begin_node("cpus")
+            .begin_node("cpu@0")
+            .property_str("compatible", "riscv")
+            .end_node()
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&blob);
+        let fdt = Fdt::new(&words).unwrap();
+
+        assert_eq!(fdt.total_size(), blob.len());
+        assert_eq!(
+            fdt.find_property("#address-cells")
+                .unwrap()
+                .unwrap()
+                .as_u32()
+                .unwrap(),
+            1
+        );
+        let cpu = fdt.find_node("/cpus/cpu@0").unwrap().unwrap();
+        assert_eq!(
+            cpu.find_property("compatible")
+                .unwrap()
+                .unwrap()
+                .as_str()
+                .unwrap(),
+            "riscv"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "all nodes must be ended")]
+    fn writer_unbalanced() {
+        let _ = FdtWriter::new().begin_node("").finish();
+    }
+
+    #[test]
+    fn resolve_alias() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("aliases")
+            .property_str("serial0", "/soc/uart@10000000")
+            .property_str("serial1", "/soc/uart@20000000")
+            .property_str("relative", "soc/uart@10000000")
+            .end_node()
+            .begin_node("soc")
+            .begin_node("uart@10000000")
+            .property_str("compatible", "ns16550a")
+            .end_node()
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        let uart = fdt.resolve_alias("serial0").unwrap().unwrap();
+        assert_eq!(uart.name().unwrap().unit_address, Some("10000000"));
+        // the alias names a node that doesn't exist
+        assert!(fdt.resolve_alias("serial1").unwrap().is_none());
+        assert!(fdt.resolve_alias("ethernet0").unwrap().is_none());
+        assert!(matches!(
+            fdt.resolve_alias("relative"),
+            Err(Error::InvalidPath)
+        ));
+
+        // no `/aliases` node at all
+        let blob = blob();
+        let fdt = Fdt::new(&blob).unwrap();
+        assert!(fdt.resolve_alias("serial0").unwrap().is_none());
+    }
+
+    #[test]
+    fn is_enabled() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("okay")
+            .property_str("status", "okay")
+            .end_node()
+            .begin_node("ok")
+            .property_str("status", "ok")
+            .end_node()
+            .begin_node("disabled")
+            .property_str("status", "disabled")
+            .end_node()
+            .begin_node("reserved")
+            .property_str("status", "reserved")
+            .end_node()
+            .begin_node("absent")
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        let enabled = |path| fdt.find_node(path).unwrap().unwrap().is_enabled().unwrap();
+        assert!(enabled("/okay"));
+        assert!(enabled("/ok"));
+        assert!(!enabled("/disabled"));
+        assert!(!enabled("/reserved"));
+        assert!(enabled("/absent"));
+    }
+
+    #[test]
+    fn find_property() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("uart@10000000")
+            .property("reg", &[0, 0, 0, 0, 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0])
+            .property_str("compatible", "ns16550a")
+            .property("interrupt-controller", &[])
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+        let uart = fdt.find_node("/uart@10000000").unwrap().unwrap();
+
+        let reg = uart.find_property("reg").unwrap().unwrap();
+        assert_eq!(reg.name, "reg");
+        assert_eq!(reg.raw, [0, 0, 0, 0, 0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+
+        let compatible = uart.find_property("compatible").unwrap().unwrap();
+        assert_eq!(compatible.raw, b"ns16550a\0");
+
+        let empty = uart.find_property("interrupt-controller").unwrap().unwrap();
+        assert!(empty.raw.is_empty());
+
+        assert!(uart.find_property("status").unwrap().is_none());
+        // only the node's own properties are searched, not the parent's or children's
+        assert!(fdt.find_property("reg").unwrap().is_none());
+    }
+
+    #[test]
+    fn cpus() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("cpus")
+            .property_u32("#address-cells", 1)
+            .property_u32("#size-cells", 0)
+            .begin_node("cpu@0")
+            .property_u32("reg", 0)
+            .property_str("compatible", "riscv")
+            .end_node()
+            .begin_node("cpu@3")
+            .property_u32("reg", 3)
+            .end_node()
+            .begin_node("cpu-map")
+            .end_node()
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        let cpus: Vec<_> = fdt.cpus().unwrap().collect().unwrap();
+        assert_eq!(cpus.len(), 2);
+        assert_eq!(cpus[0].hart_id().unwrap(), 0);
+        assert_eq!(cpus[1].hart_id().unwrap(), 3);
+        assert_eq!(
+            cpus[0].compatible().unwrap().unwrap().collect::<Vec<_>>(),
+            ["riscv"]
+        );
+        assert!(cpus[1].compatible().unwrap().is_none());
+
+        // no `/cpus` node at all
+        let written = FdtWriter::new().begin_node("").end_node().finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+        assert!(fdt.cpus().unwrap().next().unwrap().is_none());
+    }
+
+    #[test]
+    fn cpus_unsupported_address_cells() {
+        let written = FdtWriter::new()
+            .begin_node("")
+            .begin_node("cpus")
+            .property_u32("#address-cells", 3)
+            .property_u32("#size-cells", 0)
+            .begin_node("cpu@0")
+            .property("reg", &cells(&[0, 0, 0]))
+            .end_node()
+            .end_node()
+            .end_node()
+            .finish();
+        let words = to_words(&written);
+        let fdt = Fdt::new(&words).unwrap();
+
+        let cpu = fdt.cpus().unwrap().next().unwrap().unwrap();
+        assert!(matches!(cpu.hart_id(), Err(Error::InvalidCellSize)));
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2023-Present Jonas Kruckenberg
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use alloc::vec::Vec;
+
+use crate::parser::BigEndianToken;
+use crate::{DTB_MAGIC, Header};
+
+/// Serializes a devicetree into a flattened devicetree blob (DTB).
+///
+/// Nodes are emitted in the order they are opened with [`begin_node`](Self::begin_node), the first
+/// node must be the root node (named `""`) and every node must be closed again with
+/// [`end_node`](Self::end_node) before calling [`finish`](Self::finish). Properties belong to the
+/// innermost open node and must be added before any of its children.
+///
+/// The memory reservation block is always emitted empty.
+#[derive(Debug, Default)]
+pub struct FdtWriter {
+    structs: Vec<u8>,
+    strings: Vec<u8>,
+    boot_cpuid: u32,
+    depth: usize,
+}
+
+impl FdtWriter {
+    /// Creates a writer for an empty devicetree.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the ID of the "primary" CPU that is booting the system, see [`Fdt::boot_cpuid`](crate::Fdt::boot_cpuid).
+    pub fn boot_cpuid(&mut self, boot_cpuid: u32) -> &mut Self {
+        self.boot_cpuid = boot_cpuid;
+        self
+    }
+
+    /// Opens a new node as a child of the innermost open node.
+    ///
+    /// `name` includes the unit address, if any (e.g. `uart@10000000`).
+    pub fn begin_node(&mut self, name: &str) -> &mut Self {
+        self.push_token(BigEndianToken::BEGIN_NODE);
+        self.structs.extend_from_slice(name.as_bytes());
+        self.structs.push(0);
+        self.pad_structs();
+        self.depth += 1;
+        self
+    }
+
+    /// Closes the innermost open node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no open node.
+    pub fn end_node(&mut self) -> &mut Self {
+        assert!(self.depth > 0, "no open node to end");
+        self.push_token(BigEndianToken::END_NODE);
+        self.depth -= 1;
+        self
+    }
+
+    /// Adds a property with the raw, already encoded `value` to the innermost open node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no open node or `value` is longer than `u32::MAX` bytes.
+    pub fn property(&mut self, name: &str, value: &[u8]) -> &mut Self {
+        assert!(self.depth > 0, "properties must belong to a node");
+        let name_offset = self.string_offset(name);
+
+        self.push_token(BigEndianToken::PROP);
+        self.push_u32(u32::try_from(value.len()).unwrap());
+        self.push_u32(name_offset);
+        self.structs.extend_from_slice(value);
+        self.pad_structs();
+        self
+    }
+
+    /// Adds a property holding a single `u32` cell to the innermost open node.
+    pub fn property_u32(&mut self, name: &str, value: u32) -> &mut Self {
+        self.property(name, &value.to_be_bytes())
+    }
+
+    /// Adds a property holding a NUL-terminated string to the innermost open node.
+    pub fn property_str(&mut self, name: &str, value: &str) -> &mut Self {
+        let mut buf = Vec::with_capacity(value.len() + 1);
+        buf.extend_from_slice(value.as_bytes());
+        buf.push(0);
+        self.property(name, &buf)
+    }
+
+    /// Returns the finished blob.
+    ///
+    /// The blob uses the version 17 layout: the header, followed by the (empty) memory reservation
+    /// block, the structure block and the strings block. Use [`Fdt::new`](crate::Fdt::new) to parse
+    /// it, which requires the bytes to be copied into a 4-byte aligned buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a node is still open or the blob would be larger than `u32::MAX` bytes.
+    #[must_use]
+    pub fn finish(&self) -> Vec<u8> {
+        assert_eq!(self.depth, 0, "all nodes must be ended before finishing");
+
+        // header, an empty memory reservation block, structs, strings
+        let reservations_offset = Header::SIZE as usize;
+        let structs_offset = reservations_offset + 16;
+        let structs_size = self.structs.len() + 4;
+        let strings_offset = structs_offset + structs_size;
+        let strings_size = self.strings.len();
+        let total_size = strings_offset + strings_size;
+
+        let header = [
+            DTB_MAGIC,
+            u32::try_from(total_size).unwrap(),
+            u32::try_from(structs_offset).unwrap(),
+            u32::try_from(strings_offset).unwrap(),
+            u32::try_from(reservations_offset).unwrap(),
+            Header::VERSION,
+            16,
+            self.boot_cpuid,
+            u32::try_from(strings_size).unwrap(),
+            u32::try_from(structs_size).unwrap(),
+        ];
+
+        let mut out = Vec::with_capacity(total_size);
+        for word in header {
+            out.extend_from_slice(&word.to_be_bytes());
+        }
+        // the reservation block is terminated by an all-zero entry
+        out.extend_from_slice(&[0; 16]);
+        out.extend_from_slice(&self.structs);
+        out.extend_from_slice(&BigEndianToken::END.0.to_ne().to_be_bytes());
+        out.extend_from_slice(&self.strings);
+        debug_assert_eq!(out.len(), total_size);
+
+        out
+    }
+
+    /// Returns the offset of `name` in the strings block, adding it if it's not already present.
+    fn string_offset(&mut self, name: &str) -> u32 {
+        let needle = name.as_bytes();
+
+        // Any occurrence of `name` followed by a NUL is a valid string, including the tail of a
+        // longer name.
+        let existing = self
+            .strings
+            .windows(needle.len() + 1)
+            .position(|window| window[..needle.len()] == *needle && window[needle.len()] == 0);
+
+        let offset = existing.unwrap_or_else(|| {
+            let offset = self.strings.len();
+            self.strings.extend_from_slice(needle);
+            self.strings.push(0);
+            offset
+        });
+
+        u32::try_from(offset).unwrap()
+    }
+
+    fn push_token(&mut self, token: BigEndianToken) {
+        self.push_u32(token.0.to_ne());
+    }
+
+    fn push_u32(&mut self, value: u32) {
+        self.structs.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Pads the structure block with zeroes up to the next 4-byte boundary.
+    fn pad_structs(&mut self) {
+        self.structs
+            .resize(self.structs.len().next_multiple_of(4), 0);
+    }
+}
@@ -192,6 +192,15 @@ macro_rules! impl_address {
                 self.0 & (align - 1) == 0
             }
 
+            /// Aligns this address upwards to `align`.
+            ///
+            /// This is meant for trusted inputs where overflow is impossible, e.g. during boot,
+            /// use [`checked_align_up`](Self::checked_align_up) for untrusted ones.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `align` is not a power of two. Overflow is only caught in debug builds,
+            /// release builds wrap around to zero.
             #[must_use]
             #[inline]
             pub const fn align_up(self, align: usize) -> Self {
@@ -205,10 +214,15 @@ macro_rules! impl_address {
                 let aligned =
                     Self(self.0.wrapping_add(align_minus_one) & 0usize.wrapping_sub(align));
                 debug_assert!(aligned.is_aligned_to(align));
-                debug_assert!(aligned.0 >= self.0);
+                debug_assert!(aligned.0 >= self.0, "align_up: aligned address overflows");
                 aligned
             }
 
+            /// Aligns this address downwards to `align`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `align` is not a power of two.
             #[must_use]
             #[inline]
             pub const fn align_down(self, align: usize) -> Self {
@@ -221,6 +235,40 @@ macro_rules! impl_address {
                 debug_assert!(aligned.0 <= self.0);
                 aligned
             }
+
+            /// Aligns this address upwards to `align`, returning `None` if `align` is not a
+            /// power of two or the aligned address would overflow.
+            #[must_use]
+            #[inline]
+            pub const fn checked_align_up(self, align: usize) -> Option<Self> {
+                if !align.is_power_of_two() {
+                    return None;
+                }
+
+                // SAFETY: `align` has been checked to be a power of 2 above
+                let align_minus_one = unsafe { align.unchecked_sub(1) };
+
+                match self.0.checked_add(align_minus_one) {
+                    Some(addr) => Some(Self(addr & 0usize.wrapping_sub(align))),
+                    None => None,
+                }
+            }
+
+            /// Aligns this address downwards to `align`, returning `None` if `align` is not a
+            /// power of two.
+            ///
+            /// Aligning down cannot overflow, so for a valid `align` this is always `Some`. It
+            /// exists for parity with [`checked_align_up`](Self::checked_align_up) and lets callers
+            /// handle an untrusted `align` without a panic path.
+            #[must_use]
+            #[inline]
+            pub const fn checked_align_down(self, align: usize) -> Option<Self> {
+                if !align.is_power_of_two() {
+                    return None;
+                }
+
+                Some(Self(self.0 & 0usize.wrapping_sub(align)))
+            }
         }
 
         impl_address_from!($address_ty, usize);
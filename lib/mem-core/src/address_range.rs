@@ -20,7 +20,14 @@ pub trait AddressRangeExt {
     /// Returns `true` if `address` is contained in the range.
     fn contains(&self, address: &Self::Address) -> bool;
 
+    /// Returns `true` if every address in `other` is also contained in `self`.
+    ///
+    /// An empty range contains nothing, and an empty `other` is never considered contained.
+    fn contains_range(&self, other: &Self) -> bool;
+
     /// Returns `true` if there exists an address present in both ranges.
+    ///
+    /// An empty range overlaps nothing, not even a range surrounding it.
     fn overlaps(&self, other: &Self) -> bool;
 
     /// Returns the intersection of `self` and `other`.
@@ -63,8 +70,15 @@ macro_rules! impl_address_range {
                 <Self as ::core::ops::RangeBounds<$address_ty>>::contains(self, address)
             }
 
+            fn contains_range(&self, other: &Self) -> bool {
+                !other.is_empty() && self.start <= other.start && other.end <= self.end
+            }
+
             fn overlaps(&self, other: &Self) -> bool {
-                self.start < other.end && other.start < self.end
+                !self.is_empty()
+                    && !other.is_empty()
+                    && self.start < other.end
+                    && other.start < self.end
             }
 
             fn intersect(self, other: Self) -> Self {
@@ -226,9 +226,10 @@ mycelium_bitfield::bitfield! {
         const WRITE: bool;
         /// TODO explain
         const EXECUTE: bool;
-        /// TODO explain
+        /// If set, the page is accessible from user mode. Supervisor mode may only access it when
+        /// `sstatus.SUM` is set and may never execute from it.
         const USER: bool;
-        /// TODO explain
+        /// If set, the mapping exists in all address spaces.
         const GLOBAL: bool;
         /// TODO explain
         const ACCESSED: bool;
@@ -321,6 +322,8 @@ impl super::PageTableEntry for PageTableEntry {
             .with(Self::READ, attributes.allows_read())
             .with(Self::WRITE, attributes.allows_write())
             .with(Self::EXECUTE, attributes.allows_execution())
+            .with(Self::USER, attributes.is_user_accessible())
+            .with(Self::GLOBAL, attributes.is_global())
     }
 
     #[inline]
@@ -372,6 +375,8 @@ impl super::PageTableEntry for PageTableEntry {
         MemoryAttributes::new()
             .with(MemoryAttributes::READ, self.get(Self::READ))
             .with(MemoryAttributes::WRITE_OR_EXECUTE, write_or_execute)
+            .with(MemoryAttributes::USER, self.get(Self::USER))
+            .with(MemoryAttributes::GLOBAL, self.get(Self::GLOBAL))
     }
 }
 
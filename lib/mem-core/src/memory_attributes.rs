@@ -21,6 +21,15 @@ mycelium_bitfield::bitfield! {
         pub const WRITE_OR_EXECUTE: WriteOrExecute;
         /// Cacheability / ordering class of the region. See [`MemoryKind`].
         pub const KIND: MemoryKind;
+        /// If set, the region is accessible from user mode.
+        ///
+        /// Depending on the architecture, this can also revoke access from supervisor mode
+        /// (RISC-V: the `U` bit, supervisor access requires `sstatus.SUM` and execution is never
+        /// allowed).
+        pub const USER: bool;
+        /// If set, the mapping exists in all address spaces and translations can be shared across
+        /// address space IDs.
+        pub const GLOBAL: bool;
     }
 }
 
@@ -93,4 +102,14 @@ impl MemoryAttributes {
     pub fn is_device(&self) -> bool {
         matches!(self.kind(), MemoryKind::Device)
     }
+
+    /// Returns whether the region is accessible from user mode.
+    pub fn is_user_accessible(&self) -> bool {
+        self.get(Self::USER)
+    }
+
+    /// Returns whether the mapping is global, i.e. present in all address spaces.
+    pub fn is_global(&self) -> bool {
+        self.get(Self::GLOBAL)
+    }
 }
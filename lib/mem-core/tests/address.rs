@@ -5,10 +5,18 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use mem_core::VirtualAddress;
 use mem_core::arch::riscv64::Riscv64Sv39;
+use mem_core::{PhysicalAddress, VirtualAddress};
 use proptest::{prop_assert, prop_assert_eq, prop_assert_ne, proptest};
 
+const _: () = {
+    assert!(VirtualAddress::new(0x1001).align_up(0x1000).get() == 0x2000);
+    assert!(VirtualAddress::new(0x1000).align_up(0x1000).get() == 0x1000);
+    assert!(VirtualAddress::new(0).align_up(0x1000).get() == 0);
+    assert!(PhysicalAddress::new(0x1001).align_up(0x1000).get() == 0x2000);
+    assert!(PhysicalAddress::new(0x1fff).align_down(0x1000).get() == 0x1000);
+};
+
 proptest! {
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -33,4 +41,54 @@ proptest! {
         prop_assert_ne!(addr.canonicalize::<Riscv64Sv39>(), addr);
         prop_assert!(!addr.is_canonical::<Riscv64Sv39>());
     }
+
+    #[test]
+    fn checked_align_down(addr: usize, shift in 0u32..usize::BITS) {
+        let align = 1usize << shift;
+
+        let virt = VirtualAddress::new(addr);
+        prop_assert_eq!(virt.checked_align_down(align), Some(virt.align_down(align)));
+
+        let phys = PhysicalAddress::new(addr);
+        prop_assert_eq!(phys.checked_align_down(align), Some(phys.align_down(align)));
+    }
+
+    #[test]
+    fn checked_align_up(addr: usize, shift in 0u32..usize::BITS) {
+        let align = 1usize << shift;
+
+        let expected = addr
+            .checked_next_multiple_of(align)
+            .map(VirtualAddress::new);
+        prop_assert_eq!(VirtualAddress::new(addr).checked_align_up(align), expected);
+
+        let expected = addr
+            .checked_next_multiple_of(align)
+            .map(PhysicalAddress::new);
+        prop_assert_eq!(PhysicalAddress::new(addr).checked_align_up(align), expected);
+    }
+
+    #[test]
+    fn align_up_agrees_with_checked(addr: usize, shift in 0u32..usize::BITS) {
+        let align = 1usize << shift;
+
+        let virt = VirtualAddress::new(addr);
+        if let Some(aligned) = virt.checked_align_up(align) {
+            prop_assert_eq!(virt.align_up(align), aligned);
+        }
+
+        let phys = PhysicalAddress::new(addr);
+        if let Some(aligned) = phys.checked_align_up(align) {
+            prop_assert_eq!(phys.align_up(align), aligned);
+        }
+    }
+
+    #[test]
+    fn checked_align_rejects_non_power_of_two(addr: usize, align: usize) {
+        proptest::prop_assume!(!align.is_power_of_two());
+
+        let virt = VirtualAddress::new(addr);
+        prop_assert_eq!(virt.checked_align_down(align), None);
+        prop_assert_eq!(virt.checked_align_up(align), None);
+    }
 }
@@ -7,8 +7,39 @@
 
 use core::range::Range;
 
-use mem_core::{AddressRangeExt, VirtualAddress};
-use mem_testkit::proptest::any_virt;
+use mem_core::{AddressRangeExt, PhysicalAddress, VirtualAddress};
+use mem_testkit::proptest::{any_phys, any_virt};
+use proptest::prelude::*;
+
+/// Upper bound for the addresses of the naive reference tests. Small enough to enumerate every
+/// address of a range.
+const SMALL: usize = 64;
+
+/// Naive reference: the set of addresses in `start..end` (empty if inverted).
+fn addresses((start, end): (usize, usize)) -> Range<usize> {
+    Range::from(start..end.max(start))
+}
+
+fn naive_contains_range(a: (usize, usize), b: (usize, usize)) -> bool {
+    !addresses(b).is_empty()
+        && addresses(b)
+            .into_iter()
+            .all(|addr| addresses(a).contains(&addr))
+}
+
+fn naive_overlaps(a: (usize, usize), b: (usize, usize)) -> bool {
+    addresses(a)
+        .into_iter()
+        .any(|addr| addresses(b).contains(&addr))
+}
+
+fn virt_range((start, end): (usize, usize)) -> Range<VirtualAddress> {
+    Range::from(VirtualAddress::new(start)..VirtualAddress::new(end))
+}
+
+fn phys_range((start, end): (usize, usize)) -> Range<PhysicalAddress> {
+    Range::from(PhysicalAddress::new(start)..PhysicalAddress::new(end))
+}
 
 proptest::proptest! {
     #[test]
@@ -31,4 +62,57 @@ proptest::proptest! {
             proptest::prop_assert_eq!(AddressRangeExt::len(&r), end.get() - start.get());
         }
     }
+
+    #[test]
+    fn contains_range_matches_naive(
+        a in (0..SMALL, 0..SMALL),
+        b in (0..SMALL, 0..SMALL),
+    ) {
+        let expected = naive_contains_range(a, b);
+
+        prop_assert_eq!(virt_range(a).contains_range(&virt_range(b)), expected);
+        prop_assert_eq!(phys_range(a).contains_range(&phys_range(b)), expected);
+    }
+
+    #[test]
+    fn overlaps_matches_naive(
+        a in (0..SMALL, 0..SMALL),
+        b in (0..SMALL, 0..SMALL),
+    ) {
+        let expected = naive_overlaps(a, b);
+
+        prop_assert_eq!(virt_range(a).overlaps(&virt_range(b)), expected);
+        prop_assert_eq!(phys_range(a).overlaps(&phys_range(b)), expected);
+        prop_assert_eq!(virt_range(b).overlaps(&virt_range(a)), expected);
+    }
+
+    /// Over the full address space: `contains_range` implies `overlaps`, and both agree with
+    /// `intersect` being non-empty.
+    #[test]
+    fn contains_range_implies_overlaps(
+        a in (any_virt(), any_virt()),
+        b in (any_virt(), any_virt()),
+    ) {
+        let a = Range::from(a.0..a.1);
+        let b = Range::from(b.0..b.1);
+
+        if a.contains_range(&b) {
+            prop_assert!(a.overlaps(&b));
+        }
+        prop_assert_eq!(a.overlaps(&b), !a.intersect(b).is_empty());
+    }
+
+    #[test]
+    fn empty_range_overlaps_and_contains_nothing(
+        at in any_phys(),
+        other in (any_phys(), any_phys()),
+    ) {
+        let empty = Range::from(at..at);
+        let other = Range::from(other.0..other.1);
+
+        prop_assert!(!empty.overlaps(&other));
+        prop_assert!(!other.overlaps(&empty));
+        prop_assert!(!empty.contains_range(&other));
+        prop_assert!(!other.contains_range(&empty));
+    }
 }
@@ -7,17 +7,99 @@
 
 use core::alloc::Layout;
 use core::convert::Infallible;
+use core::fmt;
 use core::range::Range;
 
 use mem_core::arch::{Arch, MapsAt, PageTableEntry, PageTableLevel};
 use mem_core::{
     AddressRangeExt, AllocError, FrameAllocator, MemoryAttributes, PageSize, PhysMap,
-    PhysicalAddress, VirtualAddress,
+    PhysicalAddress, Size4KiB, VirtualAddress,
 };
 
 use crate::flush::Flush;
+use crate::regions::Regions;
 use crate::table::{Table, Visitor, marker};
 
+/// The error returned by [`HardwareAddressSpace::protect`] and [`HardwareAddressSpace::unmap_with`]
+/// when part of the range is not mapped with leaves of the requested page size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotMappedError {
+    virt: VirtualAddress,
+}
+
+impl NotMappedError {
+    /// Returns the first address in the range that is not mapped at the requested page size.
+    pub const fn address(&self) -> VirtualAddress {
+        self.virt
+    }
+}
+
+impl fmt::Display for NotMappedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "virtual address {} is not mapped at the requested page size",
+            self.virt
+        )
+    }
+}
+
+impl core::error::Error for NotMappedError {}
+
+/// The error returned by [`HardwareAddressSpace::map_stack`].
+#[derive(Debug, Clone, Copy)]
+pub enum MapStackError {
+    /// The virtual address range cannot hold the requested guard pages and at least one page of stack.
+    TooSmall,
+    /// Allocating an intermediate page table failed.
+    Alloc(AllocError),
+}
+
+impl From<AllocError> for MapStackError {
+    fn from(err: AllocError) -> Self {
+        MapStackError::Alloc(err)
+    }
+}
+
+impl fmt::Display for MapStackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapStackError::TooSmall => write!(f, "range too small for the requested guard pages"),
+            MapStackError::Alloc(err) => write!(f, "failed to map stack: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for MapStackError {}
+
+/// The error returned by [`HardwareAddressSpace::try_map_contiguous`].
+#[derive(Debug, Clone, Copy)]
+pub enum TryMapError {
+    /// Part of the range is already mapped, starting at `at`.
+    AlreadyMapped { at: VirtualAddress },
+    /// Allocating an intermediate page table failed.
+    Alloc(AllocError),
+}
+
+impl From<AllocError> for TryMapError {
+    fn from(err: AllocError) -> Self {
+        TryMapError::Alloc(err)
+    }
+}
+
+impl fmt::Display for TryMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryMapError::AlreadyMapped { at } => {
+                write!(f, "virtual address {at} is already mapped")
+            }
+            TryMapError::Alloc(err) => write!(f, "failed to map range: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for TryMapError {}
+
 pub struct HardwareAddressSpace<A: Arch> {
     arch: A,
     root_page_table: Table<A, marker::Owned>,
@@ -155,6 +237,80 @@ impl<A: Arch> HardwareAddressSpace<A> {
         None
     }
 
+    /// Returns an iterator over the mapped regions of this address space in ascending address order.
+    ///
+    /// Adjacent leaves with equal [`MemoryAttributes`] and page size are coalesced into a single
+    /// region. See [`Regions`] for details.
+    pub fn regions<'a>(&'a self, physmap: &'a PhysMap) -> Regions<'a, A> {
+        Regions::new(self.root_page_table.borrow(), &self.arch, physmap)
+    }
+
+    /// Calls `visit` for every leaf entry overlapping `range` in ascending address order, passing
+    /// the virtual address the leaf starts at, the [`PhysicalAddress`] and [`MemoryAttributes`] it
+    /// maps, and the [`PageTableLevel`] it was found at.
+    ///
+    /// Unlike [`regions`](Self::regions), leaves are reported one by one and never coalesced, which
+    /// makes this useful for debugging page table corruption. The walk is read-only and never
+    /// allocates. Note that a huge page overlapping `range.start` is reported with its own start
+    /// address, which lies before `range.start`.
+    pub fn walk(
+        &self,
+        range: Range<VirtualAddress>,
+        physmap: &PhysMap,
+        mut visit: impl FnMut(
+            VirtualAddress,
+            PhysicalAddress,
+            MemoryAttributes,
+            &'static PageTableLevel,
+        ),
+    ) {
+        walk_table(
+            &self.root_page_table.borrow(),
+            0,
+            range,
+            physmap,
+            &self.arch,
+            &mut visit,
+        );
+    }
+
+    /// Returns a new address space using `arch` that maps everything this address space maps, e.g.
+    /// to implement `fork`.
+    ///
+    /// The page table hierarchy is duplicated into tables freshly allocated from `frame_allocator`,
+    /// but leaf entries are copied as-is: both address spaces map the *same* physical frames with
+    /// the same [`MemoryAttributes`]. Copy-on-write is not implemented yet, so writes through either
+    /// address space are visible through the other.
+    ///
+    /// `arch` is taken separately instead of cloned from this address space because the new address
+    /// space will usually need its own address space ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AllocError`] if the frame allocator cannot provide the page tables. NOTE: Tables
+    /// allocated up to that point are not returned to `frame_allocator`.
+    pub fn clone_mappings(
+        &self,
+        arch: A,
+        frame_allocator: impl FrameAllocator,
+        physmap: &PhysMap,
+    ) -> Result<Self, AllocError> {
+        let mut root_page_table = Table::allocate(frame_allocator.by_ref(), physmap, &arch)?;
+
+        clone_table(
+            &self.root_page_table.borrow(),
+            &mut root_page_table.borrow_mut(),
+            frame_allocator.by_ref(),
+            physmap,
+            &arch,
+        )?;
+
+        Ok(Self {
+            arch,
+            root_page_table,
+        })
+    }
+
     /// Maps the virtual address range `virt` to *possibly discontiguous* block(s) of physical memory
     /// `phys` with the specified memory attributes.
     ///
@@ -215,6 +371,10 @@ impl<A: Arch> HardwareAddressSpace<A> {
     /// Maps the virtual address range `virt` to a continuous region of physical memory starting at `phys`
     /// with the specified memory attributes.
     ///
+    /// Every leaf is placed at exactly the page size `S`; the walk never coalesces into larger or
+    /// splits into smaller leaves. Callers that must not get huge pages (e.g. for device MMIO) pick
+    /// [`Size4KiB`](mem_core::Size4KiB).
+    ///
     /// If this returns `Ok`, the mapping is added to the address space.
     ///
     /// Note that this method **does not** establish any ordering between address space modification
@@ -285,6 +445,130 @@ impl<A: Arch> HardwareAddressSpace<A> {
         Ok(())
     }
 
+    /// Maps the virtual address range `virt` to a continuous region of physical memory starting at `phys`
+    /// with the specified memory attributes, checking first that no page in `virt` is mapped.
+    ///
+    /// Unlike [`map_contiguous`](Self::map_contiguous) this does not require the range to be unmapped;
+    /// instead the tables are walked once without modification and the first mapped page is reported,
+    /// so mapping over an existing entry never silently replaces it. A leaf of any size counts as
+    /// mapped.
+    ///
+    /// Note that this method **does not** establish any ordering between address space modification
+    /// and accesses through the mapping, nor does it imply a page table cache flush. To ensure the
+    /// new mapping is visible to the calling CPU you must call [`flush`][Flush::flush] on the returned `[Flush`].
+    ///
+    /// # Safety
+    ///
+    /// 1. `virt` must be aligned to `S`.
+    /// 2. `phys` must be aligned to `S`.
+    /// 3. The region pointed to by `phys` must be at least as large as `virt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TryMapError::AlreadyMapped`] if any page in `virt` is already mapped, in which case the
+    /// address space remains unaltered. Returns [`TryMapError::Alloc`] if the mapping cannot be
+    /// established. NOTE: The address space may remain partially altered in that case. The caller
+    /// should call *unmap* on the virtual address range upon failure.
+    pub unsafe fn try_map_contiguous<S: PageSize>(
+        &mut self,
+        virt: Range<VirtualAddress>,
+        phys: PhysicalAddress,
+        attributes: MemoryAttributes,
+        frame_allocator: impl FrameAllocator,
+        physmap: &PhysMap,
+        flush: &mut Flush,
+    ) -> Result<(), TryMapError>
+    where
+        A: MapsAt<S>,
+    {
+        let mut visitor = CheckUnmappedVisitor { next: virt.start };
+
+        self.root_page_table
+            .borrow_mut()
+            .visit::<S, _>(virt, physmap, &self.arch, &mut visitor)?;
+
+        // Safety: we checked above that the entire range is unmapped (1.), the rest is ensured by
+        // the caller.
+        unsafe {
+            self.map_contiguous::<S>(virt, phys, attributes, frame_allocator, physmap, flush)?;
+        }
+
+        Ok(())
+    }
+
+    /// Maps a stack into the virtual address range `virt`, leaving the lowest `guard_pages` 4KiB pages
+    /// unmapped so that overflowing the stack faults instead of silently corrupting memory below it.
+    /// The remainder of `virt` is mapped to the physical memory starting at `phys.start`.
+    ///
+    /// Returns the mapped part of `virt`, i.e. the usable stack.
+    ///
+    /// Note that this method **does not** establish any ordering between address space modification
+    /// and accesses through the mapping, nor does it imply a page table cache flush. To ensure the
+    /// new mapping is visible to the calling CPU you must call [`flush`][Flush::flush] on the returned `[Flush`].
+    ///
+    /// # Safety
+    ///
+    /// 1. The entire range `virt`, including the guard pages, must be unmapped.
+    /// 2. `virt` and `phys` must be aligned to 4KiB.
+    /// 3. `phys` must be at least as large as the mapped part of `virt`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MapStackError::TooSmall`] if `virt` cannot hold `guard_pages` and at least one mapped
+    /// page, in which case the address space remains unaltered. Returns [`MapStackError::Alloc`] if
+    /// the mapping cannot be established. NOTE: The address space may remain partially altered in that
+    /// case. The caller should call *unmap* on `virt` minus its lowest `guard_pages` pages upon
+    /// failure.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors map_contiguous plus the guard page count"
+    )]
+    pub unsafe fn map_stack(
+        &mut self,
+        virt: Range<VirtualAddress>,
+        phys: Range<PhysicalAddress>,
+        attributes: MemoryAttributes,
+        guard_pages: usize,
+        frame_allocator: impl FrameAllocator,
+        physmap: &PhysMap,
+        flush: &mut Flush,
+    ) -> Result<Range<VirtualAddress>, MapStackError>
+    where
+        A: MapsAt<Size4KiB>,
+    {
+        let guard_len = guard_pages
+            .checked_mul(Size4KiB::BYTES)
+            .ok_or(MapStackError::TooSmall)?;
+
+        if virt.len() <= guard_len {
+            return Err(MapStackError::TooSmall);
+        }
+
+        let stack = Range {
+            start: virt.start.add(guard_len),
+            end: virt.end,
+        };
+
+        debug_assert!(
+            phys.len() >= stack.len(),
+            "physical memory {phys:?} is too small for stack {stack:?}"
+        );
+
+        // Safety: ensured by caller
+        unsafe {
+            self.map_contiguous::<Size4KiB>(
+                stack,
+                phys.start,
+                attributes,
+                frame_allocator,
+                physmap,
+                flush,
+            )?;
+        }
+
+        Ok(stack)
+    }
+
     /// Remaps the virtual address range `virt` to new *possibly discontiguous* block(s) of physical
     /// memory `phys`. The old physical memory region is not freed.
     ///
@@ -433,6 +717,65 @@ impl<A: Arch> HardwareAddressSpace<A> {
         }
     }
 
+    /// Set the [`MemoryAttributes`] for the virtual address range `virt`, checking first that the
+    /// entire range is mapped with `S`-sized leaves.
+    ///
+    /// Unlike [`set_attributes`](Self::set_attributes) this does not require the range to be mapped;
+    /// instead the tables are walked once without modification and the first gap is reported. Only if
+    /// that succeeds are the attributes rewritten, so on `Err` the address space remains unaltered.
+    /// The physical addresses of the leaves are preserved.
+    ///
+    /// Note that this method **does not** establish any ordering between address space modification
+    /// and accesses through the mapping, nor does it imply a page table cache flush. To ensure the
+    /// updated mapping is visible to the calling CPU you must call [`flush`][Flush::flush] on the returned `[Flush`].
+    /// Only the pages in `virt` are added to `flush`.
+    ///
+    /// # Safety
+    ///
+    /// 1. `virt` must be aligned to `S`.
+    /// 2. The new attributes must not revoke access that live references into `virt` rely on
+    ///    (e.g. removing `WRITE` from memory behind a `&mut`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NotMappedError`] if any page in `virt` is not mapped with an `S`-sized leaf.
+    pub unsafe fn protect<S: PageSize>(
+        &mut self,
+        virt: Range<VirtualAddress>,
+        attributes: MemoryAttributes,
+        physmap: &PhysMap,
+        flush: &mut Flush,
+    ) -> Result<(), NotMappedError>
+    where
+        A: MapsAt<S>,
+    {
+        debug_assert!(
+            virt.len() >= S::BYTES,
+            "address range must span at least one page of size {}",
+            S::BYTES,
+        );
+        debug_assert!(
+            virt.start.is_aligned_to(S::BYTES),
+            "virtual address {} must be aligned to page size {}",
+            virt.start,
+            S::BYTES,
+        );
+
+        let mut visitor = CheckMappedVisitor { next: virt.start };
+
+        self.root_page_table
+            .borrow_mut()
+            .visit::<S, _>(virt, physmap, &self.arch, &mut visitor)?;
+
+        // Safety: we checked above that the entire range is mapped with `S`-sized leaves (1.), the
+        // alignment is ensured by the caller (2.).
+        unsafe {
+            self.set_attributes::<S>(virt, attributes, physmap, flush);
+        }
+
+        Ok(())
+    }
+
     /// Unmaps the virtual address range `virt`.
     ///
     /// Note that this method **does not** establish any ordering between address space modification
@@ -470,6 +813,7 @@ impl<A: Arch> HardwareAddressSpace<A> {
         let mut visitor = UnmapVisitor {
             frame_allocator,
             flush,
+            on_unmap: |_| {},
         };
 
         // Safety: `UnmapVisitor` is infallible.
@@ -481,6 +825,74 @@ impl<A: Arch> HardwareAddressSpace<A> {
         }
     }
 
+    /// Unmaps the virtual address range `virt`, checking first that the entire range is mapped with
+    /// `S`-sized leaves, and calls `on_unmap` with the physical address of every unmapped page.
+    ///
+    /// The pages are reported in ascending virtual address order, which lets callers hand the frames
+    /// back to their allocator. Intermediate page tables that become empty are freed through
+    /// `frame_allocator` as with [`unmap`](Self::unmap).
+    ///
+    /// Like [`protect`](Self::protect) the tables are walked once without modification first, so on
+    /// `Err` the address space remains unaltered and `on_unmap` is never called.
+    ///
+    /// Note that this method **does not** establish any ordering between address space modification
+    /// and accesses through the mapping, nor does it imply a page table cache flush. To ensure the
+    /// removal is visible to the calling CPU you must call [`flush`][Flush::flush] on the returned `[Flush`].
+    ///
+    /// # Safety
+    ///
+    /// 1. `virt` must be aligned to `S`.
+    /// 2. No live references into `virt` may exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NotMappedError`] if any page in `virt` is not mapped with an `S`-sized leaf.
+    pub unsafe fn unmap_with<S: PageSize>(
+        &mut self,
+        virt: Range<VirtualAddress>,
+        frame_allocator: impl FrameAllocator,
+        physmap: &PhysMap,
+        flush: &mut Flush,
+        on_unmap: impl FnMut(PhysicalAddress),
+    ) -> Result<(), NotMappedError>
+    where
+        A: MapsAt<S>,
+    {
+        debug_assert!(
+            virt.len() >= S::BYTES,
+            "address range must span at least one page of size {}",
+            S::BYTES,
+        );
+        debug_assert!(
+            virt.start.is_aligned_to(S::BYTES),
+            "virtual address {} must be aligned to page size {}",
+            virt.start,
+            S::BYTES,
+        );
+
+        let mut visitor = CheckMappedVisitor { next: virt.start };
+
+        self.root_page_table
+            .borrow_mut()
+            .visit::<S, _>(virt, physmap, &self.arch, &mut visitor)?;
+
+        let mut visitor = UnmapVisitor {
+            frame_allocator,
+            flush,
+            on_unmap,
+        };
+
+        // Safety: `UnmapVisitor` is infallible.
+        unsafe {
+            self.root_page_table
+                .borrow_mut()
+                .visit::<S, _>(virt, physmap, &self.arch, &mut visitor)
+                .unwrap_unchecked();
+        }
+
+        Ok(())
+    }
+
     /// Identity-maps the physical address range with the specified memory attributes.
     ///
     /// If this returns `Ok`, the mapping is added to the address space.
@@ -721,17 +1133,244 @@ where
     }
 }
 
-/// [`Visitor`] for [`unmap`](HardwareAddressSpace::unmap)
-struct UnmapVisitor<'a, F> {
+/// Recursive helper for [`HardwareAddressSpace::walk`]. `base` is the (non-canonical) virtual
+/// address mapped by entry `0` of `table`.
+fn walk_table<A: Arch>(
+    table: &Table<A, marker::Immut<'_>>,
+    base: usize,
+    range: Range<VirtualAddress>,
+    physmap: &PhysMap,
+    arch: &A,
+    visit: &mut impl FnMut(VirtualAddress, PhysicalAddress, MemoryAttributes, &'static PageTableLevel),
+) {
+    let level = table.level();
+
+    for index in 0..level.entries() {
+        let virt = base + index as usize * level.page_size();
+        let start = VirtualAddress::new(virt).canonicalize::<A>();
+
+        // Entries are visited in ascending address order, so nothing after this can overlap.
+        if start >= range.end {
+            break;
+        }
+        if start.add(level.page_size() - 1) < range.start {
+            continue;
+        }
+
+        // Safety: `index` is less than the number of entries at this level.
+        let entry = unsafe { table.get(index, physmap, arch) };
+
+        if entry.is_table() {
+            let depth = table.depth() + 1;
+            if depth as usize >= A::LEVELS.len() {
+                log::warn!(
+                    "Found a table entry at the last page table level. This indicates a malformed page table!"
+                );
+                // turn this soft warning into a hard panic in debug mode
+                debug_assert!(false);
+                continue;
+            }
+
+            // Safety: we checked the entry is a table above and know the depth is in-bounds, the
+            // subtable is borrowed for as long as its parent is.
+            let table = unsafe { Table::from_raw_parts(entry.address(), depth) };
+            walk_table(&table, virt, range, physmap, arch, visit);
+        } else if entry.is_leaf() {
+            visit(start, entry.address(), entry.attributes(), level);
+        }
+    }
+}
+
+/// Recursive helper for [`HardwareAddressSpace::clone_mappings`]. Copies every entry of `src` into
+/// the empty table `dst`, allocating a fresh subtable for every table entry.
+fn clone_table<A: Arch>(
+    src: &Table<A, marker::Immut<'_>>,
+    dst: &mut Table<A, marker::Mut<'_>>,
+    frame_allocator: &impl FrameAllocator,
+    physmap: &PhysMap,
+    arch: &A,
+) -> Result<(), AllocError> {
+    debug_assert_eq!(src.depth(), dst.depth());
+
+    for index in 0..src.level().entries() {
+        // Safety: `index` is less than the number of entries at this level.
+        let entry = unsafe { src.get(index, physmap, arch) };
+
+        if entry.is_table() {
+            let depth = src.depth() + 1;
+            if depth as usize >= A::LEVELS.len() {
+                log::warn!(
+                    "Found a table entry at the last page table level. This indicates a malformed page table!"
+                );
+                // turn this soft warning into a hard panic in debug mode
+                debug_assert!(false);
+                continue;
+            }
+
+            let frame =
+                frame_allocator.allocate_contiguous_zeroed(A::GRANULE_LAYOUT, physmap, arch)?;
+
+            // Safety: `index` is less than the number of entries at this level.
+            unsafe { dst.set(index, A::PageTableEntry::new_table(frame), physmap, arch) };
+
+            // Safety: we checked the entry is a table above and know the depth is in-bounds, the
+            // subtable is borrowed for as long as its parent is.
+            let src = unsafe { Table::from_raw_parts(entry.address(), depth) };
+            // Safety: we just allocated the subtable and linked it into `dst`, nothing else
+            // references it yet.
+            let mut dst = unsafe { Table::from_raw_parts(frame, depth) };
+
+            clone_table(&src, &mut dst, frame_allocator, physmap, arch)?;
+        } else if entry.is_leaf() {
+            // Safety: `index` is less than the number of entries at this level.
+            unsafe { dst.set(index, entry, physmap, arch) };
+        }
+    }
+
+    Ok(())
+}
+
+/// Read-only [`Visitor`] for [`protect`](HardwareAddressSpace::protect) that fails on the first
+/// page not mapped by an `S`-sized leaf.
+struct CheckMappedVisitor {
+    /// The start of the next page the walk will reach. The walk visits pages in ascending order, so
+    /// when `descend` finds no table, this is where the gap begins.
+    next: VirtualAddress,
+}
+
+impl<A, S> Visitor<A, S> for CheckMappedVisitor
+where
+    A: MapsAt<S>,
+    S: PageSize,
+{
+    type Error = NotMappedError;
+
+    fn descend(
+        &mut self,
+        table: &mut Table<A, marker::Mut<'_>>,
+        index: u16,
+        physmap: &PhysMap,
+        arch: &A,
+    ) -> Result<Option<PhysicalAddress>, NotMappedError> {
+        // Safety: the walk only descends through in-bounds indices.
+        let entry = unsafe { table.get(index, physmap, arch) };
+
+        // Vacant entries and leaves above the `S` level both mean the range is not mapped at `S`.
+        if entry.is_table() {
+            Ok(Some(entry.address()))
+        } else {
+            Err(NotMappedError { virt: self.next })
+        }
+    }
+
+    fn fill(
+        &mut self,
+        table: &mut Table<A, marker::Mut<'_>>,
+        first: u16,
+        count: u16,
+        va: VirtualAddress,
+        physmap: &PhysMap,
+        arch: &A,
+    ) -> Result<(), NotMappedError> {
+        debug_assert_eq!(va, self.next);
+
+        for i in 0..count {
+            // Safety: `first + i` is within the covered run and therefore in-bounds.
+            let entry = unsafe { table.get(first + i, physmap, arch) };
+
+            if !entry.is_leaf() {
+                return Err(NotMappedError {
+                    virt: va.add(i as usize * S::BYTES),
+                });
+            }
+        }
+
+        self.next = va.add(count as usize * S::BYTES);
+
+        Ok(())
+    }
+}
+
+/// Read-only [`Visitor`] for [`try_map_contiguous`](HardwareAddressSpace::try_map_contiguous) that
+/// fails on the first page that is already mapped.
+struct CheckUnmappedVisitor {
+    /// The start of the next page the walk will reach.
+    next: VirtualAddress,
+}
+
+impl<A, S> Visitor<A, S> for CheckUnmappedVisitor
+where
+    A: MapsAt<S>,
+    S: PageSize,
+{
+    type Error = TryMapError;
+
+    fn descend(
+        &mut self,
+        table: &mut Table<A, marker::Mut<'_>>,
+        index: u16,
+        physmap: &PhysMap,
+        arch: &A,
+    ) -> Result<Option<PhysicalAddress>, TryMapError> {
+        // Safety: the walk only descends through in-bounds indices.
+        let entry = unsafe { table.get(index, physmap, arch) };
+
+        if entry.is_table() {
+            Ok(Some(entry.address()))
+        } else if entry.is_vacant() {
+            // Nothing below a vacant entry is mapped, skip to where the next entry begins.
+            let entry_size = table.level().page_size();
+            self.next = self.next.align_down(entry_size).saturating_add(entry_size);
+            Ok(None)
+        } else {
+            // A leaf above the `S` level maps the whole entry, including `next`.
+            Err(TryMapError::AlreadyMapped { at: self.next })
+        }
+    }
+
+    fn fill(
+        &mut self,
+        table: &mut Table<A, marker::Mut<'_>>,
+        first: u16,
+        count: u16,
+        va: VirtualAddress,
+        physmap: &PhysMap,
+        arch: &A,
+    ) -> Result<(), TryMapError> {
+        debug_assert_eq!(va, self.next);
+
+        for i in 0..count {
+            // Safety: `first + i` is within the covered run and therefore in-bounds.
+            let entry = unsafe { table.get(first + i, physmap, arch) };
+
+            if !entry.is_vacant() {
+                return Err(TryMapError::AlreadyMapped {
+                    at: va.add(i as usize * S::BYTES),
+                });
+            }
+        }
+
+        self.next = va.add(count as usize * S::BYTES);
+
+        Ok(())
+    }
+}
+
+/// [`Visitor`] for [`unmap`](HardwareAddressSpace::unmap) and
+/// [`unmap_with`](HardwareAddressSpace::unmap_with)
+struct UnmapVisitor<'a, F, G> {
     frame_allocator: F,
     flush: &'a mut Flush,
+    /// Called with the physical address of every leaf that is cleared.
+    on_unmap: G,
 }
 
-impl<A, S, F> Visitor<A, S> for UnmapVisitor<'_, F>
+impl<A, S, F, G> Visitor<A, S> for UnmapVisitor<'_, F, G>
 where
     A: MapsAt<S>,
     S: PageSize,
     F: FrameAllocator,
+    G: FnMut(PhysicalAddress),
 {
     type Error = Infallible;
 
@@ -782,15 +1421,17 @@ where
         let mut entry_virt = table.entry_address(first, physmap);
 
         for _ in 0..count {
+            // Safety: `entry_virt` is within the covered run, in-bounds and aligned.
+            let old = unsafe { arch.read::<A::PageTableEntry>(entry_virt) };
             debug_assert!(
-                // Safety: `entry_virt` is within the covered run, in-bounds and aligned.
-                unsafe { arch.read::<A::PageTableEntry>(entry_virt) }.is_leaf(),
+                old.is_leaf(),
                 "virtual address range must be mapped at page size {}",
                 S::BYTES,
             );
 
             // Safety: `entry_virt` is within the covered run, in-bounds and aligned.
             unsafe { arch.write(entry_virt, A::PageTableEntry::VACANT) };
+            (self.on_unmap)(old.address());
 
             entry_virt = entry_virt.add(size_of::<A::PageTableEntry>());
         }
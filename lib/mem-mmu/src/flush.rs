@@ -5,8 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use core::mem;
 use core::range::Range;
+use core::{cmp, mem};
 
 use arrayvec::ArrayVec;
 use mem_core::VirtualAddress;
@@ -75,4 +75,53 @@ impl Flush {
     pub fn invalidate_all(&mut self) {
         *self = Flush::All;
     }
+
+    /// Merges the invalidations recorded in `other` into this `Flush`, so that both can be issued
+    /// with a single [`flush`](Self::flush).
+    ///
+    /// Ranges of `other` that overlap or touch a range already recorded widen it instead of taking up
+    /// a new slot, so the recorded ranges stay disjoint. Like [`invalidate`](Self::invalidate), this
+    /// coarsens to [`Flush::All`] once the range buffer is full.
+    ///
+    /// Note that a `Flush` does not record which address space it belongs to, so this cannot check that
+    /// `self` and `other` describe the same one. Merging `Flush`es of different address spaces is a
+    /// logic error: flushing the result through one address space's [`Arch`] leaves the stale entries
+    /// of the other in the TLB. Keep one `Flush` per address space instead.
+    pub fn merge(&mut self, other: Flush) {
+        match other {
+            Flush::Ranges(other) => {
+                for range in other {
+                    self.invalidate_coalesced(range);
+                }
+            }
+            Flush::All => *self = Flush::All,
+        }
+    }
+
+    /// Records `range` as needing TLB invalidation, widening any recorded ranges it overlaps or
+    /// touches rather than pushing a separate entry.
+    fn invalidate_coalesced(&mut self, mut range: Range<VirtualAddress>) {
+        let Flush::Ranges(ranges) = self else {
+            return;
+        };
+
+        if range.is_empty() {
+            return;
+        }
+
+        // Widening `range` can make it touch ranges it didn't before, so keep absorbing until
+        // nothing is left to merge.
+        while let Some(index) = ranges
+            .iter()
+            .position(|recorded| recorded.start <= range.end && range.start <= recorded.end)
+        {
+            let recorded = ranges.swap_remove(index);
+            range = Range {
+                start: cmp::min(recorded.start, range.start),
+                end: cmp::max(recorded.end, range.end),
+            };
+        }
+
+        self.invalidate(range);
+    }
 }
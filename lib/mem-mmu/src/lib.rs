@@ -14,14 +14,16 @@
 
 mod address_space;
 mod flush;
+mod regions;
 mod table;
 mod utils;
 
-pub use address_space::HardwareAddressSpace;
+pub use address_space::{HardwareAddressSpace, MapStackError, NotMappedError, TryMapError};
 pub use flush::Flush;
 // Typed page-size selectors, re-exported so callers name a granularity
 // (`mem_mmu::Size2MiB`) without reaching into `mem-core` or any arch module.
 pub use mem_core::{MapsAt, PageSize, Size1GiB, Size2MiB, Size4KiB, Size256TiB, Size512GiB};
+pub use regions::Regions;
 pub use table::{Table, Visitor, marker};
 // Re-exported for the `mem-testkit` emulator and out-of-crate tests; the page-walk
 // helper is otherwise an internal detail of this crate.
@@ -0,0 +1,173 @@
+// Copyright 2023-Present Jonas Kruckenberg
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::iter::FusedIterator;
+use core::range::Range;
+
+use arrayvec::ArrayVec;
+use mem_core::arch::{Arch, MAX_PAGE_TABLE_LEVELS, PageTableEntry, PageTableLevel};
+use mem_core::{MemoryAttributes, PhysMap, VirtualAddress};
+
+use crate::table::{Table, marker};
+
+/// Iterator over the mapped regions of a [`HardwareAddressSpace`](crate::HardwareAddressSpace),
+/// returned by [`regions`](crate::HardwareAddressSpace::regions).
+///
+/// Yields `(range, attributes, level)` in ascending address order, where `level` is the
+/// [`PageTableLevel`] holding the leaves. Adjacent leaves are coalesced into one region when they
+/// share both their [`MemoryAttributes`] and their page size; a change in either starts a new region.
+/// Physical contiguity is not required.
+///
+/// The walk keeps one cursor per table level on a fixed-size stack and never allocates.
+///
+/// The `end` of a region reaching the very top of the address space wraps around to zero.
+pub struct Regions<'a, A: Arch> {
+    arch: &'a A,
+    physmap: &'a PhysMap,
+    stack: ArrayVec<Cursor<'a, A>, MAX_PAGE_TABLE_LEVELS>,
+    pending: Option<Region>,
+}
+
+/// Position of the walk within a single table.
+struct Cursor<'a, A: Arch> {
+    table: Table<A, marker::Immut<'a>>,
+    /// The (non-canonical) virtual address mapped by entry `0` of `table`.
+    base: usize,
+    /// The index of the next entry to read.
+    index: u16,
+}
+
+struct Region {
+    start: VirtualAddress,
+    len: usize,
+    attributes: MemoryAttributes,
+    level: &'static PageTableLevel,
+}
+
+impl Region {
+    fn end(&self) -> VirtualAddress {
+        self.start.wrapping_add(self.len)
+    }
+
+    fn into_item(
+        self,
+    ) -> (
+        Range<VirtualAddress>,
+        MemoryAttributes,
+        &'static PageTableLevel,
+    ) {
+        let range = Range {
+            start: self.start,
+            end: self.end(),
+        };
+        (range, self.attributes, self.level)
+    }
+}
+
+impl<'a, A: Arch> Regions<'a, A> {
+    pub(crate) fn new(
+        root: Table<A, marker::Immut<'a>>,
+        arch: &'a A,
+        physmap: &'a PhysMap,
+    ) -> Self {
+        let mut stack = ArrayVec::new();
+        stack.push(Cursor {
+            table: root,
+            base: 0,
+            index: 0,
+        });
+
+        Self {
+            arch,
+            physmap,
+            stack,
+            pending: None,
+        }
+    }
+
+    /// Advances the walk to the next leaf entry, returning it as a single-page region.
+    fn next_leaf(&mut self) -> Option<Region> {
+        while let Some(cursor) = self.stack.last_mut() {
+            let level = cursor.table.level();
+
+            if cursor.index == level.entries() {
+                self.stack.pop();
+                continue;
+            }
+
+            let index = cursor.index;
+            cursor.index += 1;
+            let virt = cursor.base + index as usize * level.page_size();
+
+            // Safety: `index` is less than the number of entries at this level.
+            let entry = unsafe { cursor.table.get(index, self.physmap, self.arch) };
+
+            if entry.is_table() {
+                let depth = cursor.table.depth() + 1;
+                if depth as usize >= A::LEVELS.len() {
+                    log::warn!(
+                        "Found a table entry at the last page table level. This indicates a malformed page table!"
+                    );
+                    // turn this soft warning into a hard panic in debug mode
+                    debug_assert!(false);
+                    continue;
+                }
+
+                // Safety: we checked the entry is a table above and know the depth is in-bounds,
+                // the subtable is borrowed for as long as its parent is.
+                let table = unsafe { Table::from_raw_parts(entry.address(), depth) };
+                self.stack.push(Cursor {
+                    table,
+                    base: virt,
+                    index: 0,
+                });
+            } else if entry.is_leaf() {
+                return Some(Region {
+                    start: VirtualAddress::new(virt).canonicalize::<A>(),
+                    len: level.page_size(),
+                    attributes: entry.attributes(),
+                    level,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl<A: Arch> Iterator for Regions<'_, A> {
+    type Item = (
+        Range<VirtualAddress>,
+        MemoryAttributes,
+        &'static PageTableLevel,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(leaf) = self.next_leaf() else {
+                return self.pending.take().map(Region::into_item);
+            };
+
+            match &mut self.pending {
+                Some(pending)
+                    if pending.end() == leaf.start
+                        && pending.attributes == leaf.attributes
+                        && pending.level.page_size() == leaf.level.page_size() =>
+                {
+                    pending.len += leaf.len;
+                }
+                pending => {
+                    if let Some(region) = pending.replace(leaf) {
+                        return Some(region.into_item());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<A: Arch> FusedIterator for Regions<'_, A> {}
@@ -11,10 +11,11 @@ mod tests {
 
     use mem_core::arch::{Arch, MapsAt};
     use mem_core::{
-        AddressRangeExt, FrameAllocator, MemoryAttributes, Size4KiB, VirtualAddress, WriteOrExecute,
+        AddressRangeExt, FrameAllocator, MemoryAttributes, PageSize, PhysicalAddress, Size1GiB,
+        Size2MiB, Size4KiB, VirtualAddress, WriteOrExecute,
     };
-    use mem_mmu::Flush;
-    use mem_testkit::{archtest, Machine, MachineBuilder};
+    use mem_mmu::{Flush, MapStackError, TryMapError};
+    use mem_testkit::{archtest, EmulateArch, Machine, MachineBuilder};
 
     archtest!([
         Riscv64Sv39,
@@ -183,6 +184,978 @@ mod tests {
             assert_eq!(attrs.allows_execution(), true);
             assert_eq!(lvl.page_size(), 4096);
         }
+
+        #[test]
+        fn protect<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let frames = frame_allocator
+                .allocate_contiguous(Layout::from_size_align(2 * A::GRANULE_SIZE, A::GRANULE_SIZE).unwrap())
+                .unwrap();
+
+            let range = Range::from_start_len(VirtualAddress::new(0x7000), 2 * A::GRANULE_SIZE);
+            let read_write = MemoryAttributes::new()
+                .with(MemoryAttributes::READ, true)
+                .with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write);
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        range,
+                        frames,
+                        read_write,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            for (attributes, writable) in [(read_only, false), (read_write, true)] {
+                let mut flush = Flush::new();
+                unsafe {
+                    address_space
+                        .protect::<Size4KiB>(range, attributes, &physmap, &mut flush)
+                        .unwrap();
+                }
+
+                let Flush::Ranges(ranges) = &flush else {
+                    panic!("expected a targeted flush");
+                };
+                assert_eq!(ranges.as_slice(), [range]);
+                flush.flush(address_space.arch());
+
+                for offset in [0, A::GRANULE_SIZE, 2 * A::GRANULE_SIZE - 1] {
+                    let (phys, attrs, _) = address_space.lookup(range.start.add(offset), &physmap).unwrap();
+                    assert_eq!(phys, frames.add(offset));
+                    assert_eq!(attrs.allows_read(), true);
+                    assert_eq!(attrs.allows_write(), writable);
+                    assert_eq!(attrs.allows_execution(), false);
+                }
+            }
+        }
+
+        #[test]
+        fn protect_unmapped_gap<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+
+            // map the first and last page of `range`, leaving the middle one unmapped
+            let range: Range<VirtualAddress> = Range::from_start_len(VirtualAddress::new(0x7000), 3 * A::GRANULE_SIZE);
+            let gap = range.start.add(A::GRANULE_SIZE);
+
+            let mut flush = Flush::new();
+            for virt in [range.start, range.end.sub(A::GRANULE_SIZE)] {
+                let frame = frame_allocator
+                    .allocate_contiguous(A::GRANULE_LAYOUT)
+                    .unwrap();
+
+                unsafe {
+                    address_space
+                        .map_contiguous::<Size4KiB>(
+                            Range::from_start_len(virt, A::GRANULE_SIZE),
+                            frame,
+                            read_only,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap();
+                }
+            }
+            flush.flush(address_space.arch());
+
+            let mut flush = Flush::new();
+            let err = unsafe {
+                address_space
+                    .protect::<Size4KiB>(
+                        range,
+                        MemoryAttributes::new()
+                            .with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Execute),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap_err()
+            };
+            assert_eq!(err.address(), gap);
+
+            // nothing was modified and nothing needs flushing
+            let Flush::Ranges(ranges) = &flush else {
+                panic!("expected an empty flush");
+            };
+            assert!(ranges.is_empty());
+
+            let (_, attrs, _) = address_space.lookup(range.start, &physmap).unwrap();
+            assert_eq!(attrs, read_only);
+
+            // a range behind a vacant intermediate entry fails at its very start
+            let far = Range::from_start_len(VirtualAddress::new(0x1000_0000), A::GRANULE_SIZE);
+            let err = unsafe {
+                address_space
+                    .protect::<Size4KiB>(far, read_only, &physmap, &mut flush)
+                    .unwrap_err()
+            };
+            assert_eq!(err.address(), far.start);
+        }
+
+        #[test]
+        fn unmap_with<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let range: Range<VirtualAddress> = Range::from_start_len(VirtualAddress::new(0x7000), 3 * A::GRANULE_SIZE);
+
+            // back every page with its own frame so the reported frames are discontiguous
+            let frames: Vec<_> = (0..3_u32)
+                .map(|_| frame_allocator.allocate_contiguous(A::GRANULE_LAYOUT).unwrap())
+                .collect();
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map::<Size4KiB>(
+                        range,
+                        frames.iter().map(|frame| Range::from_start_len(*frame, A::GRANULE_SIZE)),
+                        read_only,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            let mut unmapped = Vec::new();
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .unmap_with::<Size4KiB>(
+                        range,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                        |phys| unmapped.push(phys),
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            assert_eq!(unmapped, frames);
+            for offset in [0, A::GRANULE_SIZE, 3 * A::GRANULE_SIZE - 1] {
+                assert!(address_space.lookup(range.start.add(offset), &physmap).is_none());
+            }
+        }
+
+        #[test]
+        fn unmap_with_unmapped_gap<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+
+            // map the first and last page of `range`, leaving the middle one unmapped
+            let range: Range<VirtualAddress> = Range::from_start_len(VirtualAddress::new(0x7000), 3 * A::GRANULE_SIZE);
+            let gap = range.start.add(A::GRANULE_SIZE);
+
+            let mut flush = Flush::new();
+            for virt in [range.start, range.end.sub(A::GRANULE_SIZE)] {
+                let frame = frame_allocator
+                    .allocate_contiguous(A::GRANULE_LAYOUT)
+                    .unwrap();
+
+                unsafe {
+                    address_space
+                        .map_contiguous::<Size4KiB>(
+                            Range::from_start_len(virt, A::GRANULE_SIZE),
+                            frame,
+                            read_only,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap();
+                }
+            }
+            flush.flush(address_space.arch());
+
+            let mut unmapped = Vec::new();
+            let mut flush = Flush::new();
+            let err = unsafe {
+                address_space
+                    .unmap_with::<Size4KiB>(
+                        range,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                        |phys| unmapped.push(phys),
+                    )
+                    .unwrap_err()
+            };
+            assert_eq!(err.address(), gap);
+
+            // nothing was unmapped and nothing needs flushing
+            assert!(unmapped.is_empty());
+            let Flush::Ranges(ranges) = &flush else {
+                panic!("expected an empty flush");
+            };
+            assert!(ranges.is_empty());
+
+            assert!(address_space.lookup(range.start, &physmap).is_some());
+            assert!(address_space.lookup(range.end.sub(A::GRANULE_SIZE), &physmap).is_some());
+        }
+
+        #[test]
+        fn try_map_contiguous<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let frames = frame_allocator
+                .allocate_contiguous(Layout::from_size_align(2 * A::GRANULE_SIZE, A::GRANULE_SIZE).unwrap())
+                .unwrap();
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+
+            // both a range in an existing leaf table and one behind a vacant intermediate entry
+            for start in [0x7000, 0x1000_0000] {
+                let range = Range::from_start_len(VirtualAddress::new(start), 2 * A::GRANULE_SIZE);
+
+                let mut flush = Flush::new();
+                unsafe {
+                    address_space
+                        .try_map_contiguous::<Size4KiB>(
+                            range,
+                            frames,
+                            read_only,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap();
+                }
+                flush.flush(address_space.arch());
+
+                for offset in [0, A::GRANULE_SIZE, 2 * A::GRANULE_SIZE - 1] {
+                    let (phys, attrs, _) = address_space.lookup(range.start.add(offset), &physmap).unwrap();
+                    assert_eq!(phys, frames.add(offset));
+                    assert_eq!(attrs, read_only);
+                }
+            }
+        }
+
+        #[test]
+        fn try_map_contiguous_overlapping<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x10000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read_only = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let existing_frame = frame_allocator
+                .allocate_contiguous(A::GRANULE_LAYOUT)
+                .unwrap();
+            let frames = frame_allocator
+                .allocate_contiguous(Layout::from_size_align(3 * A::GRANULE_SIZE, A::GRANULE_SIZE).unwrap())
+                .unwrap();
+
+            // the middle page of `range` is already mapped
+            let range: Range<VirtualAddress> = Range::from_start_len(VirtualAddress::new(0x7000), 3 * A::GRANULE_SIZE);
+            let existing = range.start.add(A::GRANULE_SIZE);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        Range::from_start_len(existing, A::GRANULE_SIZE),
+                        existing_frame,
+                        read_only,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            let mut flush = Flush::new();
+            let err = unsafe {
+                address_space
+                    .try_map_contiguous::<Size4KiB>(
+                        range,
+                        frames,
+                        MemoryAttributes::new()
+                            .with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Execute),
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap_err()
+            };
+            assert!(matches!(err, TryMapError::AlreadyMapped { at } if at == existing));
+
+            // nothing was modified and nothing needs flushing
+            let Flush::Ranges(ranges) = &flush else {
+                panic!("expected an empty flush");
+            };
+            assert!(ranges.is_empty());
+
+            assert!(address_space.lookup(range.start, &physmap).is_none());
+            let (phys, attrs, _) = address_space.lookup(existing, &physmap).unwrap();
+            assert_eq!(phys, existing_frame);
+            assert_eq!(attrs, read_only);
+            assert!(address_space.lookup(range.end.sub(A::GRANULE_SIZE), &physmap).is_none());
+
+            // ranges that abut the existing page on either side don't overlap it
+            for virt in [range.start, range.end.sub(A::GRANULE_SIZE)] {
+                let mut flush = Flush::new();
+                unsafe {
+                    address_space
+                        .try_map_contiguous::<Size4KiB>(
+                            Range::from_start_len(virt, A::GRANULE_SIZE),
+                            frames.add(virt.offset_from_unsigned(range.start)),
+                            read_only,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap();
+                }
+                flush.flush(address_space.arch());
+
+                let (phys, _, _) = address_space.lookup(virt, &physmap).unwrap();
+                assert_eq!(phys, frames.add(virt.offset_from_unsigned(range.start)));
+            }
+        }
+
+        #[test]
+        fn regions<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x10000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let read_write = read.with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write);
+            let read_execute = read.with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Execute);
+
+            let mappings = [
+                (Range::from_start_len(VirtualAddress::new(0x7000), 2 * A::GRANULE_SIZE), read),
+                (Range::from_start_len(VirtualAddress::new(0xB000), A::GRANULE_SIZE), read_write),
+                (Range::from_start_len(VirtualAddress::new(0x1000_0000), 3 * A::GRANULE_SIZE), read_execute),
+            ];
+
+            let mut flush = Flush::new();
+            for (virt, attributes) in mappings {
+                let frames = frame_allocator
+                    .allocate_contiguous(Layout::from_size_align(virt.len(), A::GRANULE_SIZE).unwrap())
+                    .unwrap();
+
+                unsafe {
+                    address_space
+                        .map_contiguous::<Size4KiB>(
+                            virt,
+                            frames,
+                            attributes,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap();
+                }
+            }
+            flush.flush(address_space.arch());
+
+            let regions: Vec<_> = address_space.regions(&physmap).collect();
+
+            // the three mappings in address order, followed by the physmap in the upper half
+            assert_eq!(regions.len(), 4);
+            for ((range, attrs, lvl), (virt, attributes)) in regions.iter().zip(mappings) {
+                assert_eq!(*range, virt);
+                assert_eq!(*attrs, attributes);
+                assert_eq!(lvl.page_size(), 4096);
+            }
+            assert_eq!(regions[3].0.start, A::DEFAULT_PHYSMAP_BASE);
+            assert_eq!(regions[3].1, read_write);
+        }
+
+        #[test]
+        fn regions_split<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x10000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let read_write = read.with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write);
+
+            // Three virtually contiguous runs: a change of attributes and a change of page size
+            // must each start a new region. The leaves are never dereferenced, so `phys` need not
+            // be backed by machine memory.
+            let small_read = Range::from_start_len(VirtualAddress::new(0x1F_E000), Size4KiB::BYTES);
+            let small_read_write = Range::from_start_len(VirtualAddress::new(0x1F_F000), Size4KiB::BYTES);
+            let huge_read_write = Range::from_start_len(VirtualAddress::new(0x20_0000), Size2MiB::BYTES);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        small_read,
+                        PhysicalAddress::new(0x4000_0000),
+                        read,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        small_read_write,
+                        PhysicalAddress::new(0x4000_1000),
+                        read_write,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size2MiB>(
+                        huge_read_write,
+                        PhysicalAddress::new(0x4020_0000),
+                        read_write,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            let regions: Vec<_> = address_space
+                .regions(&physmap)
+                .map(|(range, attrs, lvl)| (range, attrs, lvl.page_size()))
+                .take(3)
+                .collect();
+
+            assert_eq!(
+                regions,
+                [
+                    (small_read, read, Size4KiB::BYTES),
+                    (small_read_write, read_write, Size4KiB::BYTES),
+                    (huge_read_write, read_write, Size2MiB::BYTES),
+                ]
+            );
+        }
+
+        #[test]
+        fn walk<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x10000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+
+            // Two 4KiB pages right below a 2MiB page. The leaves are never dereferenced, so `phys`
+            // need not be backed by machine memory.
+            let small = Range::from_start_len(VirtualAddress::new(0x1F_E000), 2 * Size4KiB::BYTES);
+            let huge = Range::from_start_len(VirtualAddress::new(0x20_0000), Size2MiB::BYTES);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        small,
+                        PhysicalAddress::new(0x4000_0000),
+                        read,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size2MiB>(
+                        huge,
+                        PhysicalAddress::new(0x4020_0000),
+                        read,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            let mut leaves = Vec::new();
+            address_space.walk(
+                Range::from(VirtualAddress::new(0)..VirtualAddress::new(0x40_0000)),
+                &physmap,
+                |virt, phys, attributes, level| {
+                    assert_eq!(attributes, read);
+                    leaves.push((virt, phys, level.page_size()));
+                },
+            );
+
+            assert_eq!(
+                leaves,
+                [
+                    (VirtualAddress::new(0x1F_E000), PhysicalAddress::new(0x4000_0000), Size4KiB::BYTES),
+                    (VirtualAddress::new(0x1F_F000), PhysicalAddress::new(0x4000_1000), Size4KiB::BYTES),
+                    (VirtualAddress::new(0x20_0000), PhysicalAddress::new(0x4020_0000), Size2MiB::BYTES),
+                ]
+            );
+
+            // a range starting inside the huge page still reports it, but nothing before it
+            let mut count = 0_usize;
+            address_space.walk(
+                Range::from(VirtualAddress::new(0x30_0000)..VirtualAddress::new(0x30_1000)),
+                &physmap,
+                |virt, _, _, level| {
+                    assert_eq!(virt, huge.start);
+                    assert_eq!(level.page_size(), Size2MiB::BYTES);
+                    count += 1;
+                },
+            );
+            assert_eq!(count, 1);
+        }
+
+        #[test]
+        fn map_user_global<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xC000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let frames = frame_allocator
+                .allocate_contiguous(Layout::from_size_align(2 * A::GRANULE_SIZE, A::GRANULE_SIZE).unwrap())
+                .unwrap();
+
+            let read = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let user = read
+                .with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write)
+                .with(MemoryAttributes::USER, true);
+            let global = read.with(MemoryAttributes::GLOBAL, true);
+
+            let user_page = Range::from_start_len(VirtualAddress::new(0x7000), A::GRANULE_SIZE);
+            let global_page = Range::from_start_len(user_page.end, A::GRANULE_SIZE);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        user_page,
+                        frames,
+                        user,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        global_page,
+                        frames.add(A::GRANULE_SIZE),
+                        global,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            let (phys, attrs, _) = address_space.lookup(user_page.start, &physmap).unwrap();
+            assert_eq!(phys, frames);
+            assert_eq!(attrs, user);
+            assert!(attrs.is_user_accessible());
+            assert!(!attrs.is_global());
+
+            let (phys, attrs, _) = address_space.lookup(global_page.start, &physmap).unwrap();
+            assert_eq!(phys, frames.add(A::GRANULE_SIZE));
+            assert_eq!(attrs, global);
+            assert!(!attrs.is_user_accessible());
+            assert!(attrs.is_global());
+        }
+
+        #[test]
+        fn clone_mappings<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x20000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let read = MemoryAttributes::new().with(MemoryAttributes::READ, true);
+            let read_write = read.with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write);
+
+            let small = Range::from_start_len(VirtualAddress::new(0x1F_E000), 2 * Size4KiB::BYTES);
+            let huge = Range::from_start_len(VirtualAddress::new(0x20_0000), Size2MiB::BYTES);
+            let far = Range::from_start_len(VirtualAddress::new(0x4000_0000), Size4KiB::BYTES);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        small,
+                        PhysicalAddress::new(0x4000_0000),
+                        read,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size2MiB>(
+                        huge,
+                        PhysicalAddress::new(0x4020_0000),
+                        read_write,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        far,
+                        PhysicalAddress::new(0x8000_0000),
+                        read_write,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            // the clone is never activated, so it can share the address space ID of the active one
+            let arch = EmulateArch::new(machine.clone());
+            let mut clone = address_space
+                .clone_mappings(arch, frame_allocator.by_ref(), &physmap)
+                .unwrap();
+
+            let probes = [
+                small.start,
+                small.start.add(Size4KiB::BYTES + 42),
+                huge.start.add(0x1234),
+                far.start.add(8),
+                far.end,
+                VirtualAddress::new(0x1F_D000),
+                physmap.phys_to_virt(machine.memory_regions().next().unwrap().start),
+            ];
+            for virt in probes {
+                let expected = address_space.lookup(virt, &physmap);
+                let actual = clone.lookup(virt, &physmap);
+                assert_eq!(
+                    actual.map(|(phys, attrs, level)| (phys, attrs, level.page_size())),
+                    expected.map(|(phys, attrs, level)| (phys, attrs, level.page_size())),
+                    "translation of {virt} differs",
+                );
+            }
+
+            // the clone has its own tables, mapping into it leaves the original untouched
+            let extra = Range::from_start_len(VirtualAddress::new(0x1F_C000), Size4KiB::BYTES);
+            let mut flush = Flush::new();
+            unsafe {
+                clone
+                    .map_contiguous::<Size4KiB>(
+                        extra,
+                        PhysicalAddress::new(0x9000_0000),
+                        read,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(clone.arch());
+
+            assert!(clone.lookup(extra.start, &physmap).is_some());
+            assert!(address_space.lookup(extra.start, &physmap).is_none());
+        }
+
+        #[test]
+        fn map_stack<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0x20000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let attributes = MemoryAttributes::new()
+                .with(MemoryAttributes::READ, true)
+                .with(MemoryAttributes::WRITE_OR_EXECUTE, WriteOrExecute::Write);
+
+            for (guard_pages, base) in [(1, 0x10_0000), (2, 0x20_0000)] {
+                let virt = Range::from_start_len(VirtualAddress::new(base), 4 * Size4KiB::BYTES);
+                let guard_len = guard_pages * Size4KiB::BYTES;
+
+                let phys = Range::from_start_len(
+                    frame_allocator
+                        .allocate_contiguous(Layout::from_size_align(virt.len() - guard_len, A::GRANULE_SIZE).unwrap())
+                        .unwrap(),
+                    virt.len() - guard_len,
+                );
+
+                let mut flush = Flush::new();
+                let stack = unsafe {
+                    address_space
+                        .map_stack(
+                            virt,
+                            phys,
+                            attributes,
+                            guard_pages,
+                            frame_allocator.by_ref(),
+                            &physmap,
+                            &mut flush,
+                        )
+                        .unwrap()
+                };
+                flush.flush(address_space.arch());
+
+                assert_eq!(stack, Range::from(virt.start.add(guard_len)..virt.end));
+
+                // every byte of the guard is unmapped
+                for offset in [0, guard_len / 2, guard_len - 1] {
+                    assert!(address_space.lookup(virt.start.add(offset), &physmap).is_none());
+                }
+
+                // the stack is mapped from its lowest to its highest byte
+                for offset in [0, stack.len() - 1] {
+                    let (mapped, attrs, _) = address_space.lookup(stack.start.add(offset), &physmap).unwrap();
+                    assert_eq!(mapped, phys.start.add(offset));
+                    assert_eq!(attrs, attributes);
+                }
+            }
+        }
+
+        #[test]
+        fn map_stack_too_small<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xA000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let virt = Range::from_start_len(VirtualAddress::new(0x10_0000), 2 * Size4KiB::BYTES);
+            let phys = Range::from_start_len(PhysicalAddress::new(0x4000_0000), 2 * Size4KiB::BYTES);
+
+            // the guards would leave no room for the stack itself
+            for guard_pages in [2, 3, usize::MAX] {
+                let mut flush = Flush::new();
+                let res = unsafe {
+                    address_space.map_stack(
+                        virt,
+                        phys,
+                        MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                        guard_pages,
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                };
+                assert!(matches!(res, Err(MapStackError::TooSmall)));
+
+                let Flush::Ranges(ranges) = &flush else {
+                    panic!("expected an empty flush");
+                };
+                assert!(ranges.is_empty());
+            }
+
+            assert!(address_space.regions(&physmap).all(|(range, ..)| !range.overlaps(&virt)));
+        }
+
+        #[test]
+        fn map_2mib<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xA000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            // The leaf maps but never dereferences `phys`, so it need not be backed by machine memory.
+            let phys = PhysicalAddress::new(0x4020_0000);
+            let virt = Range::from_start_len(VirtualAddress::new(0x20_0000), 2 * Size2MiB::BYTES);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size2MiB>(
+                        virt,
+                        phys,
+                        MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            for offset in [0, Size2MiB::BYTES - 1, Size2MiB::BYTES, 2 * Size2MiB::BYTES - 1] {
+                let (mapped, _, lvl) = address_space.lookup(virt.start.add(offset), &physmap).unwrap();
+                assert_eq!(mapped, phys.add(offset));
+                assert_eq!(lvl.page_size(), Size2MiB::BYTES);
+            }
+        }
+
+        #[test]
+        fn map_1gib<A: Arch + MapsAt<Size4KiB> + MapsAt<Size1GiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xA000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            // The leaf maps but never dereferences `phys`, so it need not be backed by machine memory.
+            let phys = PhysicalAddress::new(0x8000_0000);
+            let virt = Range::from_start_len(VirtualAddress::new(0x4000_0000), Size1GiB::BYTES);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size1GiB>(
+                        virt,
+                        phys,
+                        MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            for offset in [0, 42, Size1GiB::BYTES - 1] {
+                let (mapped, _, lvl) = address_space.lookup(virt.start.add(offset), &physmap).unwrap();
+                assert_eq!(mapped, phys.add(offset));
+                assert_eq!(lvl.page_size(), Size1GiB::BYTES);
+            }
+            assert!(address_space.lookup(virt.end, &physmap).is_none());
+        }
+
+        #[test]
+        fn lookup_boundaries<A: Arch + MapsAt<Size4KiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([
+                    Layout::from_size_align(0x10000, A::GRANULE_SIZE).unwrap()
+                ])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let frames = frame_allocator
+                .allocate_contiguous(Layout::from_size_align(2 * A::GRANULE_SIZE, A::GRANULE_SIZE).unwrap())
+                .unwrap();
+
+            let range = Range::from_start_len(VirtualAddress::new(0x7000), 2 * A::GRANULE_SIZE);
+
+            let mut flush = Flush::new();
+            unsafe {
+                address_space
+                    .map_contiguous::<Size4KiB>(
+                        range,
+                        frames,
+                        MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                        frame_allocator.by_ref(),
+                        &physmap,
+                        &mut flush,
+                    )
+                    .unwrap();
+            }
+            flush.flush(address_space.arch());
+
+            // inside, including the first and last byte of each page
+            for offset in [0, 42, A::GRANULE_SIZE - 1, A::GRANULE_SIZE, 2 * A::GRANULE_SIZE - 1] {
+                let (phys, attrs, lvl) = address_space.lookup(range.start.add(offset), &physmap).unwrap();
+                assert_eq!(phys, frames.add(offset));
+                assert_eq!(attrs.allows_read(), true);
+                assert_eq!(lvl.page_size(), 4096);
+            }
+
+            // just outside either end of the mapping, but within the same leaf table
+            assert!(address_space.lookup(range.start.sub(1), &physmap).is_none());
+            assert!(address_space.lookup(range.end, &physmap).is_none());
+
+            // far away, where the walk stops at a vacant intermediate entry
+            assert!(address_space.lookup(VirtualAddress::new(0x1000_0000), &physmap).is_none());
+        }
+
+        /// Huge leaves are never silently split: a `virt` not aligned to the requested
+        /// page size is a caller bug, caught by the debug assertions.
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "must be aligned to page size")]
+        fn map_2mib_misaligned_virt<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xA000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let virt = Range::from_start_len(VirtualAddress::new(0x20_1000), Size2MiB::BYTES);
+
+            let mut flush = Flush::new();
+            let _ = unsafe {
+                address_space.map_contiguous::<Size2MiB>(
+                    virt,
+                    PhysicalAddress::new(0x4020_0000),
+                    MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                    frame_allocator.by_ref(),
+                    &physmap,
+                    &mut flush,
+                )
+            };
+        }
+
+        /// See [`map_2mib_misaligned_virt`]; the same holds for the physical side.
+        #[test]
+        #[cfg(debug_assertions)]
+        #[should_panic(expected = "must be aligned to page size")]
+        fn map_2mib_misaligned_phys<A: Arch + MapsAt<Size4KiB> + MapsAt<Size2MiB>>() {
+            let machine: Machine<A> = MachineBuilder::new()
+                .with_memory_regions([Layout::from_size_align(0xA000, A::GRANULE_SIZE).unwrap()])
+                .finish();
+
+            let (mut address_space, frame_allocator, physmap) = machine.bootstrap_address_space::<Size4KiB>(A::DEFAULT_PHYSMAP_BASE);
+
+            let virt = Range::from_start_len(VirtualAddress::new(0x20_0000), Size2MiB::BYTES);
+
+            let mut flush = Flush::new();
+            let _ = unsafe {
+                address_space.map_contiguous::<Size2MiB>(
+                    virt,
+                    PhysicalAddress::new(0x4020_1000),
+                    MemoryAttributes::new().with(MemoryAttributes::READ, true),
+                    frame_allocator.by_ref(),
+                    &physmap,
+                    &mut flush,
+                )
+            };
+        }
     });
 }
 
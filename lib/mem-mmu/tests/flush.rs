@@ -12,6 +12,92 @@ use mem_mmu::Flush;
 use mem_testkit::proptest::any_virt;
 use proptest::prelude::*;
 
+fn range(start: usize, end: usize) -> Range<VirtualAddress> {
+    Range::from(VirtualAddress::new(start)..VirtualAddress::new(end))
+}
+
+/// Returns the recorded ranges of `flush` sorted by start address.
+fn recorded(flush: &Flush) -> Vec<Range<VirtualAddress>> {
+    let Flush::Ranges(ranges) = flush else {
+        panic!("expected a targeted flush");
+    };
+    let mut ranges = ranges.to_vec();
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+#[test]
+fn merge_adjacent_ranges() {
+    let mut flush = Flush::new();
+    flush.invalidate(range(0x1000, 0x2000));
+
+    let mut other = Flush::new();
+    other.invalidate(range(0x2000, 0x3000));
+    other.invalidate(range(0x0000, 0x1000));
+    flush.merge(other);
+
+    assert_eq!(recorded(&flush), [range(0x0000, 0x3000)]);
+}
+
+#[test]
+fn merge_overlapping_ranges() {
+    let mut flush = Flush::new();
+    flush.invalidate(range(0x1000, 0x4000));
+
+    let mut other = Flush::new();
+    other.invalidate(range(0x3000, 0x6000));
+    flush.merge(other);
+
+    assert_eq!(recorded(&flush), [range(0x1000, 0x6000)]);
+}
+
+#[test]
+fn merge_disjoint_ranges() {
+    let mut flush = Flush::new();
+    flush.invalidate(range(0x1000, 0x2000));
+
+    let mut other = Flush::new();
+    other.invalidate(range(0x8000, 0x9000));
+    other.invalidate(range(0x4000, 0x5000));
+    flush.merge(other);
+
+    assert_eq!(
+        recorded(&flush),
+        [
+            range(0x1000, 0x2000),
+            range(0x4000, 0x5000),
+            range(0x8000, 0x9000)
+        ]
+    );
+}
+
+#[test]
+fn merge_bridging_range() {
+    let mut flush = Flush::new();
+    flush.invalidate(range(0x1000, 0x2000));
+    flush.invalidate(range(0x3000, 0x4000));
+
+    // fills the hole between the two recorded ranges, collapsing all three into one
+    let mut other = Flush::new();
+    other.invalidate(range(0x2000, 0x3000));
+    flush.merge(other);
+
+    assert_eq!(recorded(&flush), [range(0x1000, 0x4000)]);
+}
+
+#[test]
+fn merge_all() {
+    let mut flush = Flush::new();
+    flush.invalidate(range(0x1000, 0x2000));
+    flush.merge(Flush::All);
+    assert!(matches!(flush, Flush::All));
+
+    let mut other = Flush::new();
+    other.invalidate(range(0x1000, 0x2000));
+    flush.merge(other);
+    assert!(matches!(flush, Flush::All));
+}
+
 proptest! {
     /// `invalidate` must never panic, regardless of how many ranges are pushed, and
     /// every pushed range must remain covered by the resulting `Flush`.
@@ -40,3 +126,53 @@ proptest! {
         }
     }
 }
+
+proptest! {
+    /// Every range of either side must remain covered after `merge`, and the recorded ranges must
+    /// not overlap or touch each other.
+    #[test]
+    fn merge_covers_every_range(
+        a in proptest::collection::vec(
+            (any_virt(), any_virt())
+                .prop_map(|(a, b)| Range::from(a.min(b)..a.max(b))),
+            0..24,
+        ),
+        b in proptest::collection::vec(
+            (any_virt(), any_virt())
+                .prop_map(|(a, b)| Range::from(a.min(b)..a.max(b))),
+            0..24,
+        ),
+    ) {
+        let mut flush = Flush::new();
+        for range in &a {
+            flush.merge({
+                let mut single = Flush::new();
+                single.invalidate(*range);
+                single
+            });
+        }
+
+        let mut other = Flush::new();
+        for range in &b {
+            other.invalidate(*range);
+        }
+        flush.merge(other);
+
+        match flush {
+            Flush::All => {}
+            Flush::Ranges(recorded) => {
+                for range in a.iter().chain(&b).filter(|range| !range.is_empty()) {
+                    prop_assert!(
+                        recorded.iter().any(|recorded| recorded.start <= range.start && range.end <= recorded.end)
+                    );
+                }
+
+                for (i, x) in recorded.iter().enumerate() {
+                    for y in &recorded[i + 1..] {
+                        prop_assert!(x.end < y.start || y.end < x.start);
+                    }
+                }
+            }
+        }
+    }
+}
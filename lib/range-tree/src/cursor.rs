@@ -332,6 +332,8 @@ impl<I: RangeTreeIndex, V, A: Allocator> RawCursor<I, V, A, &'_ mut RangeTree<I,
         // Save the next leaf pointer since it is overwritten by insertion.
         let next_leaf = unsafe { node.next_leaf(&self.tree.leaf) };
 
+        self.tree.len += 1;
+
         // Insert the new pivot and value in the leaf. Use a fast path for
         // inserting at the end of a node. This helps with common cases when
         // appending to the end of a tree.
@@ -573,6 +575,8 @@ impl<I: RangeTreeIndex, V, A: Allocator> RawCursor<I, V, A, &'_ mut RangeTree<I,
         };
         let (start, value) = unsafe { node.value(pos, &self.tree.leaf).assume_init_read() };
 
+        self.tree.len -= 1;
+
         // Remove the pivot and value from the node.
         // Safety: node is at `LEAF` height
         unsafe {
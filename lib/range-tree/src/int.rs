@@ -61,6 +61,13 @@ pub(crate) trait RangeTreeInteger: Copy + Debug + Send + Sync + Unpin {
     /// Increments a raw integer by 1.
     fn increment(int: Self::Raw) -> Self::Raw;
 
+    /// Decrements a raw integer by 1.
+    fn decrement(int: Self::Raw) -> Self::Raw;
+
+    /// Returns the raw integer of the last element of a run of `len` elements starting at `start`,
+    /// or `None` if it would not fit in the integer.
+    fn checked_last_of(start: Self::Raw, len: Self) -> Option<Self::Raw>;
+
     /// Array of pivots used for SIMD comparison in `rank`.
     ///
     /// This must have the same layout as `[Self; Self::B]`.
@@ -111,6 +118,17 @@ macro_rules! impl_int {
                     int.wrapping_add(1)
                 }
 
+                #[inline]
+                fn decrement(int: Self::Raw) -> Self::Raw {
+                    int.wrapping_sub(1)
+                }
+
+                #[inline]
+                fn checked_last_of(start: Self::Raw, len: Self) -> Option<Self::Raw> {
+                    let last = Self::from_raw(start)?.checked_add(len.get() - 1)?;
+                    Some(last.to_raw())
+                }
+
                 type Pivots = CacheAligned<[Self::Raw; Self::B]>;
 
                 #[inline]
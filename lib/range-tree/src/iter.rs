@@ -407,6 +407,75 @@ impl<'a, I: RangeTreeIndex, V, A: Allocator> Iterator for Gaps<'a, I, V, A> {
     }
 }
 
+/// An iterator over the gaps between the ranges of a [`RangeTree`] within some bounds.
+///
+/// This `struct` is created by [`RangeTree::gaps_in`].
+pub struct GapsIn<'a, I: RangeTreeIndex, V, A: Allocator = Global> {
+    raw: RawIter<I::Int>,
+    /// Start of the next gap, or `None` once the bounds are exhausted.
+    next_start: Option<I>,
+    /// Last index of the bounds.
+    last: I,
+    tree: &'a RangeTree<I, V, A>,
+}
+
+impl<'a, I: RangeTreeIndex, V, A: Allocator> Iterator for GapsIn<'a, I, V, A> {
+    type Item = RangeInclusive<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = int_from_pivot(self.last);
+
+        while let Some(start) = self.next_start {
+            // Safety: iterators only operate on leaf nodes
+            let next_range = unsafe { self.raw.next(&self.tree.leaf) }
+                .map(|(end, value)| {
+                    // Safety: `RawIter` yields only initialized entries
+                    let (range_start, _) = unsafe { value.as_ref() };
+                    (int_from_pivot(*range_start), end.to_raw())
+                })
+                .filter(|&(range_start, _)| I::Int::cmp(range_start, last).is_le());
+
+            let Some((range_start, range_last)) = next_range else {
+                // No more ranges within the bounds, the rest of them is the trailing gap.
+                self.next_start = None;
+                return Some(RangeInclusive {
+                    start,
+                    last: self.last,
+                });
+            };
+
+            self.next_start = if I::Int::cmp(range_last, last).is_lt() {
+                pivot_from_int(I::Int::increment(range_last))
+            } else {
+                None
+            };
+
+            // if the range does not start right where the gap does, yield the space in between
+            if I::Int::cmp(int_from_pivot(start), range_start).is_lt() {
+                return Some(RangeInclusive {
+                    start,
+                    last: pivot_from_int(I::Int::decrement(range_start))?,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, I: RangeTreeIndex, V, A: Allocator> FusedIterator for GapsIn<'a, I, V, A> {}
+
+impl<'a, I: RangeTreeIndex, V, A: Allocator> Clone for GapsIn<'a, I, V, A> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw.clone(),
+            next_start: self.next_start,
+            last: self.last,
+            tree: self.tree,
+        }
+    }
+}
+
 fn is_empty<I>(gap: impl RangeBounds<I>) -> bool
 where
     I: RangeTreeIndex,
@@ -631,6 +700,36 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
             prev_end: Some(Bound::Unbounded),
         }
     }
+
+    /// An iterator over the gaps between the ranges of a [`RangeTree`] that lie within `bounds`.
+    ///
+    /// Unlike [`gaps`](Self::gaps) the gaps are clamped to `bounds` and yielded as inclusive
+    /// ranges: the first gap starts at `bounds.start` if no range covers it, the last gap ends at
+    /// `bounds.last` if no range covers it, and an empty tree yields `bounds` itself. Gaps are
+    /// yielded in ascending order and are never empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `bounds.start` is greater than `bounds.last`.
+    /// In release builds the iterator yields no gaps.
+    pub fn gaps_in(&self, bounds: impl Into<RangeInclusive<I>>) -> GapsIn<'_, I, V, A> {
+        let bounds = bounds.into();
+        let start = int_from_pivot(bounds.start);
+        debug_assert!(
+            !I::Int::cmp(start, int_from_pivot(bounds.last)).is_gt(),
+            "RangeTree::gaps_in called with reversed bounds (start > last); \
+             empty iterator returned in release builds",
+        );
+
+        GapsIn {
+            raw: self.raw_iter_from(start),
+            next_start: I::Int::cmp(start, int_from_pivot(bounds.last))
+                .is_le()
+                .then_some(bounds.start),
+            last: bounds.last,
+            tree: self,
+        }
+    }
 }
 
 impl<I: RangeTreeIndex, V, A: Allocator> IntoIterator for RangeTree<I, V, A> {
@@ -672,9 +771,12 @@ impl<'a, I: RangeTreeIndex, V, A: Allocator> IntoIterator for &'a mut RangeTree<
 #[cfg(test)]
 mod tests {
     use core::num::NonZeroU64;
-    use core::ops::Bound;
+    use core::ops::{Bound, RangeBounds};
     use core::range::RangeInclusive;
 
+    use rand::rngs::SmallRng;
+    use rand::{RngExt, SeedableRng};
+
     use crate::RangeTree;
 
     /// In debug builds the reversed-bounds assert in [`RangeTree::range`] is
@@ -717,4 +819,178 @@ mod tests {
             .collect();
         assert!(entries.is_empty());
     }
+
+    fn nz(n: u64) -> NonZeroU64 {
+        NonZeroU64::new(n).unwrap()
+    }
+
+    fn incl(start: u64, last: u64) -> RangeInclusive<NonZeroU64> {
+        RangeInclusive {
+            start: nz(start),
+            last: nz(last),
+        }
+    }
+
+    fn tree_with(ranges: &[(u64, u64)]) -> RangeTree<NonZeroU64, ()> {
+        let mut tree = RangeTree::try_new().unwrap();
+        for &(start, last) in ranges {
+            tree.insert(incl(start, last), ()).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn gaps_in_empty_tree() {
+        let tree = tree_with(&[]);
+        let gaps: Vec<_> = tree.gaps_in(incl(1, 100)).collect();
+        assert_eq!(gaps, [incl(1, 100)]);
+    }
+
+    #[test]
+    fn gaps_in_includes_boundary_gaps() {
+        let tree = tree_with(&[(10, 19), (30, 39), (40, 49), (70, 79)]);
+        let gaps: Vec<_> = tree.gaps_in(incl(1, 100)).collect();
+        assert_eq!(
+            gaps,
+            [incl(1, 9), incl(20, 29), incl(50, 69), incl(80, 100)]
+        );
+    }
+
+    #[test]
+    fn gaps_in_clamps_to_bounds() {
+        let tree = tree_with(&[(10, 19), (30, 39), (70, 79)]);
+
+        // bounds start and end inside of ranges: no leading or trailing gap
+        let gaps: Vec<_> = tree.gaps_in(incl(15, 75)).collect();
+        assert_eq!(gaps, [incl(20, 29), incl(40, 69)]);
+
+        // bounds strictly between two ranges
+        let gaps: Vec<_> = tree.gaps_in(incl(45, 55)).collect();
+        assert_eq!(gaps, [incl(45, 55)]);
+
+        // bounds entirely covered by a range
+        let gaps: Vec<_> = tree.gaps_in(incl(31, 38)).collect();
+        assert!(gaps.is_empty());
+
+        // bounds touching the ranges on both ends
+        let gaps: Vec<_> = tree.gaps_in(incl(20, 69)).collect();
+        assert_eq!(gaps, [incl(20, 29), incl(40, 69)]);
+    }
+
+    #[test]
+    fn gaps_in_up_to_max() {
+        let tree = tree_with(&[(1, 9), (u64::MAX - 9, u64::MAX)]);
+
+        let gaps: Vec<_> = tree.gaps_in(incl(1, u64::MAX)).collect();
+        assert_eq!(gaps, [incl(10, u64::MAX - 10)]);
+    }
+
+    #[test]
+    fn gaps_in_across_many_leaves() {
+        // enough ranges to split the root, so the walk has to follow the leaf linked list
+        let ranges: Vec<_> = (1..200).map(|i| (i * 10, i * 10 + 4)).collect();
+        let tree = tree_with(&ranges);
+
+        let gaps: Vec<_> = tree.gaps_in(incl(1, 2000)).collect();
+
+        let mut expected = vec![incl(1, 9)];
+        expected.extend((1..199).map(|i| incl(i * 10 + 5, i * 10 + 9)));
+        expected.push(incl(1995, 2000));
+        assert_eq!(gaps, expected);
+    }
+
+    #[test]
+    fn values_match_iter() {
+        let mut tree: RangeTree<NonZeroU64, u64> = RangeTree::try_new().unwrap();
+        for i in 1..200 {
+            tree.insert(incl(i * 10, i * 10 + 4), i).unwrap();
+        }
+
+        let values: Vec<_> = tree.values().copied().collect();
+        let expected: Vec<_> = tree.iter().map(|(_, &value)| value).collect();
+        assert_eq!(values, expected);
+
+        for value in tree.values_mut() {
+            *value *= 2;
+        }
+        assert!(
+            tree.iter()
+                .all(|(range, &value)| range.start.get() == value * 5)
+        );
+    }
+
+    #[test]
+    fn iter_mut_updates_values() {
+        let mut tree: RangeTree<NonZeroU64, u64> = RangeTree::try_new().unwrap();
+        for i in 1..200 {
+            tree.insert(incl(i * 10, i * 10 + 4), i).unwrap();
+        }
+
+        for (range, value) in &mut tree {
+            assert_eq!(range.start.get(), *value * 10);
+            *value += 1;
+        }
+        assert!(
+            tree.iter()
+                .all(|(range, &value)| range.start.get() == (value - 1) * 10)
+        );
+
+        // stopping early leaves the remaining values untouched
+        for (_, value) in tree.iter_mut().take(10) {
+            *value = 0;
+        }
+        assert_eq!(tree.values().filter(|&&value| value == 0).count(), 10);
+        assert_eq!(tree.get(nz(110)), Some(&12));
+    }
+
+    fn random_bound(rng: &mut SmallRng, n: u64) -> Bound<NonZeroU64> {
+        match rng.random_range(0..3_u32) {
+            0 => Bound::Included(nz(n)),
+            1 => Bound::Excluded(nz(n)),
+            _ => Bound::Unbounded,
+        }
+    }
+
+    #[test]
+    fn range_matches_filtered_iter() {
+        let mut rng = SmallRng::seed_from_u64(0x7a9e);
+
+        // 300 ranges `i*10+1..=i*10+5` spread over enough leaves to exercise seeking.
+        let tree = tree_with(
+            &(0..300)
+                .map(|i| (i * 10 + 1, i * 10 + 5))
+                .collect::<Vec<_>>(),
+        );
+
+        for _ in 0..500_u32 {
+            // Keep `lo < hi` so no combination of bound kinds is reversed.
+            let lo = rng.random_range(1..3000_u64);
+            let hi = rng.random_range(lo + 1..=3010);
+            let bounds = (random_bound(&mut rng, lo), random_bound(&mut rng, hi));
+
+            let expected: Vec<_> = tree
+                .iter()
+                .filter(|(range, _)| bounds.contains(&range.last))
+                .collect();
+            let actual: Vec<_> = tree.range(bounds).collect();
+            assert_eq!(actual, expected, "bounds: {bounds:?}");
+        }
+    }
+
+    #[test]
+    fn range_at_the_end_of_the_index_space() {
+        let tree = tree_with(&[(1, 9), (u64::MAX - 9, u64::MAX)]);
+
+        let all: Vec<_> = tree
+            .range(..=nz(u64::MAX))
+            .map(|(range, _)| range)
+            .collect();
+        assert_eq!(all, [incl(1, 9), incl(u64::MAX - 9, u64::MAX)]);
+
+        let last: Vec<_> = tree.range(nz(u64::MAX)..).map(|(range, _)| range).collect();
+        assert_eq!(last, [incl(u64::MAX - 9, u64::MAX)]);
+
+        let first: Vec<_> = tree.range(..nz(u64::MAX)).map(|(range, _)| range).collect();
+        assert_eq!(first, [incl(1, 9)]);
+    }
 }
@@ -30,16 +30,25 @@ pub use iter::*;
 use node::{NodePool, NodeRef, UninitNodeRef};
 use stack::Height;
 
-use crate::int::int_from_pivot;
+use crate::int::{int_from_pivot, pivot_from_int};
 use crate::node::NodePos;
 
 /// Error indicating range overlaps with an existing range in the tree.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct OverlapError;
+pub struct OverlapError<I> {
+    /// The range already stored in the tree that the inserted range overlaps.
+    ///
+    /// If the inserted range overlaps several stored ranges, this is one of them.
+    pub conflicting: range::RangeInclusive<I>,
+}
 
-impl fmt::Display for OverlapError {
+impl<I: fmt::Debug> fmt::Display for OverlapError<I> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "overlapping range")
+        write!(
+            f,
+            "range overlaps existing range {:?}..={:?}",
+            self.conflicting.start, self.conflicting.last
+        )
     }
 }
 
@@ -99,6 +108,8 @@ pub struct RangeTree<I: RangeTreeIndex, V, A: Allocator = Global> {
     leaf: NodePool<I::Int, (I, V)>,
     height: Height<I::Int>,
     root: NodeRef,
+    /// Number of ranges stored in the tree.
+    len: usize,
     alloc: A,
 }
 
@@ -110,6 +121,13 @@ impl<I: RangeTreeIndex + fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug
     }
 }
 
+impl<I: RangeTreeIndex, V, A: Allocator + Default> Default for RangeTree<I, V, A> {
+    /// Creates an empty [`RangeTree`] in the default allocator, see [`new_in`](Self::new_in).
+    fn default() -> Self {
+        Self::new_in(A::default())
+    }
+}
+
 impl<I: RangeTreeIndex, V> RangeTree<I, V, Global> {
     /// Creates a new, empty [`RangeTree`].
     ///
@@ -122,6 +140,20 @@ impl<I: RangeTreeIndex, V> RangeTree<I, V, Global> {
     pub fn try_new() -> Result<Self, AllocError> {
         Self::try_new_in(Global)
     }
+
+    /// Creates a new, empty [`RangeTree`].
+    ///
+    /// This requires an initial memory allocation on creation, use [`try_new`](Self::try_new) to
+    /// handle allocation failure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocating the initial node of the tree failed.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::new_in(Global)
+    }
 }
 
 impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
@@ -139,6 +171,7 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
             leaf: NodePool::new(),
             height: Height::LEAF,
             root: NodeRef::ZERO,
+            len: 0,
             alloc,
         };
         let root = unsafe { out.leaf.alloc_node(&out.alloc)? };
@@ -151,6 +184,20 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
         Ok(out)
     }
 
+    /// Creates a new, empty [`RangeTree`] with the given allocator.
+    ///
+    /// This requires an initial memory allocation on creation, use [`try_new_in`](Self::try_new_in)
+    /// to handle allocation failure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocating the initial node of the tree failed.
+    #[inline]
+    #[must_use]
+    pub fn new_in(alloc: A) -> Self {
+        Self::try_new_in(alloc).unwrap()
+    }
+
     /// Initializes the root node to the leaf node at offset zero.
     ///
     /// # Safety
@@ -190,6 +237,7 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
 
         // Re-initialize the root node.
         self.height = Height::LEAF;
+        self.len = 0;
 
         // Safety: we allocated `root` from the leaf node pool above
         unsafe {
@@ -212,6 +260,25 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
         }
     }
 
+    /// Returns the number of ranges in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the range with the smallest indices and its value, or `None` if the map is empty.
+    #[inline]
+    pub fn first(&self) -> Option<(range::RangeInclusive<I>, &V)> {
+        self.cursor().entry()
+    }
+
+    /// Returns the range with the largest indices and its value, or `None` if the map is empty.
+    #[inline]
+    pub fn last(&self) -> Option<(range::RangeInclusive<I>, &V)> {
+        let mut cursor = self.cursor_at(Bound::Unbounded);
+        if cursor.prev() { cursor.entry() } else { None }
+    }
+
     /// Returns a reference to the value corresponding to the pivot.
     #[inline]
     pub fn get(&self, search: I) -> Option<&V> {
@@ -225,6 +292,12 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
         }
     }
 
+    /// Returns `true` if `point` is covered by one of the ranges in the map.
+    #[inline]
+    pub fn contains_point(&self, point: I) -> bool {
+        self.get(point).is_some()
+    }
+
     /// Returns a mutable reference to the value corresponding to the pivot.
     #[inline]
     pub fn get_mut(&mut self, search: I) -> Option<&mut V> {
@@ -250,19 +323,77 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
     ///
     /// # Errors
     ///
-    /// If the entry could not be inserted, either because allocation failed
-    ///
-    /// Returns `Err` when insertion fails either because allocating the required memory failed
-    /// or because the
+    /// Returns `Err(OverlapError)` if `range` overlaps an existing range, the error carries the
+    /// stored range that blocked the insertion. The map is left unchanged in that case.
     #[inline]
     pub fn insert(
         &mut self,
         range: impl Into<range::RangeInclusive<I>>,
         value: V,
-    ) -> Result<(), OverlapError> {
+    ) -> Result<(), OverlapError<I>> {
         // TODO remove this once `new_range_api` is stable.
         let range = range.into();
 
+        self.insertion_cursor(range)?.insert(range, value);
+
+        Ok(())
+    }
+
+    /// Inserts a range and associated value into the map, merging it with its immediate
+    /// neighbours if they are adjacent to `range` and carry an equal value.
+    ///
+    /// E.g. inserting `5..=9` next to an existing `10..=14` with the same value leaves a single
+    /// `5..=14` entry in the map. If the map only ever grows through this method, no two adjacent
+    /// entries will share a value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(OverlapError)` if `range` overlaps an existing range, in which case the map is
+    /// left unchanged.
+    pub fn insert_coalescing(
+        &mut self,
+        range: impl Into<range::RangeInclusive<I>>,
+        value: V,
+    ) -> Result<(), OverlapError<I>>
+    where
+        V: PartialEq,
+    {
+        let mut range = range.into();
+        let mut cursor = self.insertion_cursor(range)?;
+
+        // The cursor points at the successor of `range`, merge with it first.
+        if let Some((next, next_value)) = cursor.entry()
+            && I::Int::increment(int_from_pivot(range.last)) == int_from_pivot(next.start)
+            && *next_value == value
+        {
+            range.last = next.last;
+            cursor.remove();
+        }
+
+        if cursor.prev() {
+            if let Some((prev, prev_value)) = cursor.entry()
+                && I::Int::increment(int_from_pivot(prev.last)) == int_from_pivot(range.start)
+                && *prev_value == value
+            {
+                range.start = prev.start;
+                // Removing leaves the cursor at the insertion position.
+                cursor.remove();
+            } else {
+                cursor.next();
+            }
+        }
+
+        cursor.insert(range, value);
+
+        Ok(())
+    }
+
+    /// Returns a cursor positioned where `range` would be inserted, or `Err(OverlapError)` if it
+    /// overlaps an existing range.
+    fn insertion_cursor(
+        &mut self,
+        range: range::RangeInclusive<I>,
+    ) -> Result<CursorMut<'_, I, V, A>, OverlapError<I>> {
         // Safety: we immediately initialize the cursor below
         let mut cursor = unsafe { CursorMut::uninit(self) };
         cursor.seek(int_from_pivot(range.last));
@@ -272,25 +403,73 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
                 existing.start.to_int().to_raw(),
                 range.last.to_int().to_raw(),
             )
-            .is_lt()
+            .is_le()
         {
-            return Err(OverlapError);
+            return Err(OverlapError {
+                conflicting: existing,
+            });
         }
 
         if cursor.prev() {
             if let Some((prev, _)) = cursor.entry()
-                && I::Int::cmp(prev.last.to_int().to_raw(), range.start.to_int().to_raw()).is_gt()
+                && I::Int::cmp(prev.last.to_int().to_raw(), range.start.to_int().to_raw()).is_ge()
             {
-                // Overlap detected: previous range ends after new range starts
-                return Err(OverlapError);
+                // Overlap detected: previous range ends at or after new range starts
+                return Err(OverlapError { conflicting: prev });
             }
 
             cursor.next(); // Move back to insertion position
         }
 
-        cursor.insert(range, value);
+        Ok(cursor)
+    }
 
-        Ok(())
+    /// Allocates a range of `size` indices within `bounds` and inserts it into the map with
+    /// `value`, returning the allocated range.
+    ///
+    /// The range is placed at the start of the first gap (in ascending order) large enough to hold
+    /// it. Returns `None` if no such gap exists, in which case the map is left unchanged.
+    #[inline]
+    pub fn allocate(
+        &mut self,
+        size: I::Int,
+        bounds: impl Into<range::RangeInclusive<I>>,
+        value: V,
+    ) -> Option<range::RangeInclusive<I>> {
+        self.allocate_with(size, bounds, |_| value)
+    }
+
+    /// Allocates a range of `size` indices within `bounds` and inserts it into the map with the
+    /// value returned by `f`, returning the allocated range.
+    ///
+    /// `f` is passed the allocated range and is only called if a large enough gap was found. See
+    /// [`allocate`](Self::allocate) for the placement policy.
+    pub fn allocate_with(
+        &mut self,
+        size: I::Int,
+        bounds: impl Into<range::RangeInclusive<I>>,
+        f: impl FnOnce(range::RangeInclusive<I>) -> V,
+    ) -> Option<range::RangeInclusive<I>> {
+        let range = self.gaps_in(bounds).find_map(|gap| {
+            let last = I::Int::checked_last_of(int_from_pivot(gap.start), size)?;
+
+            if I::Int::cmp(last, int_from_pivot(gap.last)).is_gt() {
+                return None;
+            }
+
+            Some(range::RangeInclusive {
+                start: gap.start,
+                last: pivot_from_int(last)?,
+            })
+        })?;
+
+        // The range lies entirely within a gap so this cannot overlap, but go through the checked
+        // `insert` anyway so a bug in the gap search can never corrupt the map.
+        let res = self.insert(range, f(range));
+        debug_assert!(res.is_ok(), "allocated range overlaps an existing range");
+        res.ok()?;
+
+        Some(range)
     }
 
     /// Removes a pivot from the map, returning the value at the pivot if the pivot
@@ -313,6 +492,82 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
         }
     }
 
+    /// Retains only the ranges specified by the predicate.
+    ///
+    /// In other words, removes all ranges for which `f` returns `false`. The ranges are visited in
+    /// ascending order and `f` may modify the values of the ranges it retains.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(range::RangeInclusive<I>, &mut V) -> bool,
+    {
+        let mut cursor = self.cursor_mut();
+        while let Some((range, value)) = cursor.entry_mut() {
+            if f(range, value) {
+                cursor.next();
+            } else {
+                // removing moves the cursor to the next element already
+                cursor.remove();
+            }
+        }
+    }
+
+    /// Splits the map in two at `at`, returning a new map with all ranges from `at` onwards.
+    ///
+    /// A range straddling `at` is split: `self` keeps the part before `at` and the returned map
+    /// receives the part starting at `at`, both with a clone of the value.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Err(AllocError)` if allocating the initial node of the new map failed, in which case
+    /// `self` is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if allocating further nodes for the new map fails, like [`insert`](Self::insert).
+    pub fn split_off(&mut self, at: I) -> Result<Self, AllocError>
+    where
+        A: Clone,
+        V: Clone,
+    {
+        let mut other = Self::try_new_in(self.alloc.clone())?;
+        // Ranges are moved in ascending order, so they are simply appended to `other`.
+        let mut tail = other.cursor_mut_at(Bound::Unbounded);
+
+        // The cursor points at the first range ending at or after `at`.
+        let mut cursor = self.cursor_mut_at(Bound::Included(at));
+
+        if let Some((range, value)) = cursor.entry()
+            && I::Int::cmp(range.start.to_int().to_raw(), at.to_int().to_raw()).is_lt()
+        {
+            // `range.start < at` so `at - 1` can't be zero.
+            let before_at = pivot_from_int(I::Int::decrement(int_from_pivot(at))).unwrap();
+            let lower = range::RangeInclusive {
+                start: range.start,
+                last: before_at,
+            };
+
+            let value = value.clone();
+            let (range, value) = cursor.replace(lower, value);
+            let upper = range::RangeInclusive {
+                start: at,
+                last: range.last,
+            };
+            tail.insert(upper, value);
+            tail.next();
+
+            cursor.next();
+        }
+
+        while !cursor.is_end() {
+            let (range, value) = cursor.remove();
+            tail.insert(range, value);
+            tail.next();
+        }
+
+        Ok(other)
+    }
+
     /// Assert as many invariants about the tree as possible
     ///
     /// # Panics
@@ -332,6 +587,9 @@ impl<I: RangeTreeIndex, V, A: Allocator> RangeTree<I, V, A> {
         // Ensure the linked list of leaf nodes is properly terminated.
         // Safety: `last_leaf` is only updated with leaf NodeRefs
         assert_eq!(unsafe { last_leaf.unwrap().next_leaf(&self.leaf) }, None);
+
+        // The cached length must match the number of entries.
+        assert_eq!(self.iter().count(), self.len);
     }
 
     fn check_node(
@@ -506,3 +764,482 @@ impl<I: RangeTreeIndex, V, A: Allocator> Drop for RangeTree<I, V, A> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroU64;
+    use core::ops::Bound;
+    use core::range::RangeInclusive;
+
+    use rand::rngs::SmallRng;
+    use rand::{RngExt, SeedableRng};
+
+    use crate::{OverlapError, RangeTree};
+
+    fn nz(n: u64) -> NonZeroU64 {
+        NonZeroU64::new(n).unwrap()
+    }
+
+    fn incl(start: u64, last: u64) -> RangeInclusive<NonZeroU64> {
+        RangeInclusive {
+            start: nz(start),
+            last: nz(last),
+        }
+    }
+
+    /// A tree with the gaps `1..=9` (9), `20..=24` (5), `30..=49` (20) and `60..=100` (41) within
+    /// `1..=100`.
+    fn fragmented() -> RangeTree<NonZeroU64, u32> {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(10, 19), 0).unwrap();
+        tree.insert(incl(25, 29), 0).unwrap();
+        tree.insert(incl(50, 59), 0).unwrap();
+        tree
+    }
+
+    #[test]
+    fn allocate_first_fit() {
+        let mut tree = fragmented();
+
+        // fits into the very first gap
+        assert_eq!(tree.allocate(nz(4), incl(1, 100), 1), Some(incl(1, 4)));
+        // the remainder of the first gap fits exactly
+        assert_eq!(tree.allocate(nz(5), incl(1, 100), 2), Some(incl(5, 9)));
+        // the first gap is exhausted, the second one fits exactly
+        assert_eq!(tree.allocate(nz(5), incl(1, 100), 2), Some(incl(20, 24)));
+        // skips the (now) too small first gap
+        assert_eq!(tree.allocate(nz(6), incl(1, 100), 3), Some(incl(30, 35)));
+        // only the last gap is large enough
+        assert_eq!(tree.allocate(nz(30), incl(1, 100), 4), Some(incl(60, 89)));
+        // nothing is large enough anymore
+        assert_eq!(tree.allocate(nz(15), incl(1, 100), 5), None);
+
+        tree.assert_valid();
+
+        assert_eq!(tree.get(nz(1)), Some(&1));
+        assert_eq!(tree.get(nz(9)), Some(&2));
+        assert_eq!(tree.get(nz(24)), Some(&2));
+        assert_eq!(tree.get(nz(35)), Some(&3));
+        assert_eq!(tree.get(nz(89)), Some(&4));
+        assert_eq!(tree.get(nz(90)), None);
+    }
+
+    #[test]
+    fn allocate_respects_bounds() {
+        let mut tree = fragmented();
+
+        assert_eq!(tree.allocate(nz(5), incl(35, 100), 1), Some(incl(35, 39)));
+        // the gap `60..=100` is clamped to `60..=64` by the bounds
+        assert_eq!(tree.allocate(nz(6), incl(55, 64), 2), None);
+        assert_eq!(tree.allocate(nz(5), incl(55, 64), 2), Some(incl(60, 64)));
+
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn allocate_with_passes_range() {
+        let mut tree: RangeTree<NonZeroU64, RangeInclusive<NonZeroU64>> =
+            RangeTree::try_new().unwrap();
+
+        for _ in 0..200_u32 {
+            let range = tree.allocate_with(nz(3), incl(1, u64::MAX), |range| range);
+            assert!(range.is_some());
+        }
+        tree.assert_valid();
+
+        for (i, (range, value)) in tree.iter().enumerate() {
+            let start = i as u64 * 3 + 1;
+            assert_eq!(range, incl(start, start + 2));
+            assert_eq!(*value, range);
+        }
+    }
+
+    #[test]
+    fn allocate_at_the_end_of_the_index_space() {
+        let mut tree: RangeTree<NonZeroU64, ()> = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, u64::MAX - 2), ()).unwrap();
+
+        assert_eq!(tree.allocate(nz(3), incl(1, u64::MAX), ()), None);
+        assert_eq!(
+            tree.allocate(nz(2), incl(1, u64::MAX), ()),
+            Some(incl(u64::MAX - 1, u64::MAX))
+        );
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn len_tracks_inserts_and_removes() {
+        let mut rng = SmallRng::seed_from_u64(0x5eed);
+        let mut tree: RangeTree<NonZeroU64, ()> = RangeTree::try_new().unwrap();
+        assert_eq!(tree.len(), 0);
+
+        // Every slot `n` is the range `n*4+1..=n*4+3`, so ranges never overlap.
+        for _ in 0..2000_u32 {
+            let slot = rng.random_range(0..256_u64);
+            let start = slot * 4 + 1;
+
+            if rng.random_bool(0.6) {
+                let _ = tree.insert(incl(start, start + 2), ());
+            } else {
+                tree.remove(nz(start));
+            }
+
+            assert_eq!(tree.len(), tree.iter().count());
+        }
+        tree.assert_valid();
+
+        // The cursor paths must keep the count in sync as well.
+        tree.cursor_mut_at(Bound::Unbounded)
+            .insert(incl(2000, 2001), ());
+        assert_eq!(tree.len(), tree.iter().count());
+        tree.cursor_mut().remove();
+        assert_eq!(tree.len(), tree.iter().count());
+
+        tree.clear();
+        assert_eq!(tree.len(), 0);
+        assert!(tree.is_empty());
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn first_and_last_empty() {
+        let tree: RangeTree<NonZeroU64, u32> = RangeTree::try_new().unwrap();
+        assert_eq!(tree.first(), None);
+        assert_eq!(tree.last(), None);
+    }
+
+    #[test]
+    fn first_and_last_single() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(5, 9), 'a').unwrap();
+        assert_eq!(tree.first(), Some((incl(5, 9), &'a')));
+        assert_eq!(tree.last(), Some((incl(5, 9), &'a')));
+    }
+
+    #[test]
+    fn first_and_last_many() {
+        let mut tree = RangeTree::try_new().unwrap();
+        // Enough entries to span several leaves, inserted out of order.
+        for i in (0..500_u64).rev().step_by(2).chain((0..500_u64).step_by(2)) {
+            tree.insert(incl(i * 4 + 1, i * 4 + 3), i).unwrap();
+        }
+
+        assert_eq!(tree.first(), Some((incl(1, 3), &0)));
+        assert_eq!(tree.last(), Some((incl(1997, 1999), &499)));
+
+        tree.remove(nz(1));
+        tree.remove(nz(1999));
+        assert_eq!(tree.first(), Some((incl(5, 7), &1)));
+        assert_eq!(tree.last(), Some((incl(1993, 1995), &498)));
+    }
+
+    #[test]
+    fn contains_point() {
+        let tree = fragmented();
+
+        // inside and exactly on the inclusive endpoints
+        assert!(tree.contains_point(nz(12)));
+        assert!(tree.contains_point(nz(10)));
+        assert!(tree.contains_point(nz(19)));
+        assert!(tree.contains_point(nz(25)));
+        assert!(tree.contains_point(nz(29)));
+
+        // in a gap, before the first and after the last range
+        assert!(!tree.contains_point(nz(20)));
+        assert!(!tree.contains_point(nz(24)));
+        assert!(!tree.contains_point(nz(1)));
+        assert!(!tree.contains_point(nz(9)));
+        assert!(!tree.contains_point(nz(60)));
+        assert!(!tree.contains_point(nz(u64::MAX)));
+
+        let empty: RangeTree<NonZeroU64, u32> = RangeTree::try_new().unwrap();
+        assert!(!empty.contains_point(nz(1)));
+    }
+
+    #[test]
+    fn overlap_reports_left_neighbor() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(5, 9), 'a').unwrap();
+        tree.insert(incl(20, 24), 'b').unwrap();
+
+        assert_eq!(
+            tree.insert(incl(7, 12), 'c'),
+            Err(OverlapError {
+                conflicting: incl(5, 9)
+            })
+        );
+        // Touching the last index is an overlap too.
+        assert_eq!(
+            tree.insert(incl(9, 12), 'c'),
+            Err(OverlapError {
+                conflicting: incl(5, 9)
+            })
+        );
+        assert_eq!(tree.len(), 2);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn overlap_reports_right_neighbor() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(5, 9), 'a').unwrap();
+        tree.insert(incl(20, 24), 'b').unwrap();
+
+        assert_eq!(
+            tree.insert(incl(15, 22), 'c'),
+            Err(OverlapError {
+                conflicting: incl(20, 24)
+            })
+        );
+        // Touching the first index is an overlap too.
+        assert_eq!(
+            tree.insert(incl(15, 20), 'c'),
+            Err(OverlapError {
+                conflicting: incl(20, 24)
+            })
+        );
+        assert_eq!(tree.len(), 2);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn overlap_reports_containment() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(5, 9), 'a').unwrap();
+        tree.insert(incl(20, 24), 'b').unwrap();
+
+        // The new range lies within an existing one.
+        assert_eq!(
+            tree.insert(incl(21, 23), 'c'),
+            Err(OverlapError {
+                conflicting: incl(20, 24)
+            })
+        );
+        // The new range covers an existing one entirely.
+        assert_eq!(
+            tree.insert(incl(12, 30), 'c'),
+            Err(OverlapError {
+                conflicting: incl(20, 24)
+            })
+        );
+        assert_eq!(tree.len(), 2);
+
+        // Adjacent but not overlapping ranges are accepted.
+        tree.insert(incl(10, 19), 'c').unwrap();
+        assert_eq!(tree.len(), 3);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_left() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, 4), 'a').unwrap();
+        tree.insert_coalescing(incl(5, 9), 'a').unwrap();
+
+        assert_eq!(tree.iter().collect::<Vec<_>>(), [(incl(1, 9), &'a')]);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_right() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(10, 14), 'a').unwrap();
+        tree.insert_coalescing(incl(5, 9), 'a').unwrap();
+
+        assert_eq!(tree.iter().collect::<Vec<_>>(), [(incl(5, 14), &'a')]);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_both_sides() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, 4), 'a').unwrap();
+        tree.insert(incl(10, 14), 'a').unwrap();
+        tree.insert(incl(20, 24), 'a').unwrap();
+        tree.insert_coalescing(incl(5, 9), 'a').unwrap();
+
+        assert_eq!(
+            tree.iter().collect::<Vec<_>>(),
+            [(incl(1, 14), &'a'), (incl(20, 24), &'a')]
+        );
+        assert_eq!(tree.len(), 2);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_different_values() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, 4), 'a').unwrap();
+        tree.insert(incl(10, 14), 'b').unwrap();
+        tree.insert_coalescing(incl(5, 9), 'c').unwrap();
+
+        assert_eq!(
+            tree.iter().collect::<Vec<_>>(),
+            [(incl(1, 4), &'a'), (incl(5, 9), &'c'), (incl(10, 14), &'b')]
+        );
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_not_adjacent() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, 3), 'a').unwrap();
+        tree.insert(incl(11, 14), 'a').unwrap();
+        tree.insert_coalescing(incl(5, 9), 'a').unwrap();
+
+        assert_eq!(tree.len(), 3);
+        assert_eq!(
+            tree.insert_coalescing(incl(9, 10), 'a'),
+            Err(OverlapError {
+                conflicting: incl(5, 9)
+            })
+        );
+        assert_eq!(tree.len(), 3);
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn insert_coalescing_many() {
+        let mut tree = RangeTree::try_new().unwrap();
+        // Insert every other slot first, then fill the holes so everything collapses into one.
+        for i in (0..400_u64).step_by(2).chain((1..400_u64).step_by(2)) {
+            tree.insert_coalescing(incl(i * 4 + 1, i * 4 + 4), ())
+                .unwrap();
+            tree.assert_valid();
+        }
+
+        assert_eq!(tree.iter().collect::<Vec<_>>(), [(incl(1, 1600), &())]);
+    }
+
+    /// A tree with `n` adjacent ranges of length 4, each holding its own index.
+    fn numbered(n: u64) -> RangeTree<NonZeroU64, u64> {
+        let mut tree = RangeTree::try_new().unwrap();
+        for i in 0..n {
+            tree.insert(incl(i * 4 + 1, i * 4 + 4), i).unwrap();
+        }
+        tree
+    }
+
+    #[test]
+    fn retain_every_other() {
+        let mut tree = numbered(500);
+        tree.retain(|range, value| {
+            assert_eq!(range, incl(*value * 4 + 1, *value * 4 + 4));
+            *value *= 10;
+            *value % 20 == 0
+        });
+        tree.assert_valid();
+
+        assert_eq!(tree.len(), 250);
+        assert!(
+            tree.iter()
+                .map(|(range, value)| (range, *value))
+                .eq((0..500_u64)
+                    .step_by(2)
+                    .map(|i| (incl(i * 4 + 1, i * 4 + 4), i * 10)))
+        );
+    }
+
+    #[test]
+    fn retain_none() {
+        let mut tree = numbered(500);
+        tree.retain(|_, _| false);
+        tree.assert_valid();
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.iter().next(), None);
+
+        // the tree is still usable afterwards
+        tree.insert(incl(1, 4), 0).unwrap();
+        tree.assert_valid();
+    }
+
+    #[test]
+    fn retain_all() {
+        let mut tree = numbered(500);
+        let mut visited = 0_u64;
+        tree.retain(|_, value| {
+            assert_eq!(*value, visited);
+            visited += 1;
+            true
+        });
+        tree.assert_valid();
+
+        assert_eq!(visited, 500);
+        assert_eq!(tree.len(), 500);
+        assert!(tree.values().copied().eq(0..500));
+    }
+
+    #[test]
+    fn split_off_between_ranges() {
+        let mut tree = numbered(100);
+        let other = tree.split_off(nz(201)).unwrap();
+        tree.assert_valid();
+        other.assert_valid();
+
+        assert_eq!(tree.len(), 50);
+        assert_eq!(other.len(), 50);
+        assert_eq!(tree.last(), Some((incl(197, 200), &49)));
+        assert_eq!(other.first(), Some((incl(201, 204), &50)));
+        assert!(other.values().copied().eq(50..100));
+    }
+
+    #[test]
+    fn split_off_inside_range() {
+        let mut tree = RangeTree::try_new().unwrap();
+        tree.insert(incl(1, 9), 'a').unwrap();
+        tree.insert(incl(10, 19), 'b').unwrap();
+        tree.insert(incl(30, 39), 'c').unwrap();
+
+        let other = tree.split_off(nz(15)).unwrap();
+        tree.assert_valid();
+        other.assert_valid();
+
+        assert_eq!(
+            tree.iter().collect::<Vec<_>>(),
+            [(incl(1, 9), &'a'), (incl(10, 14), &'b')]
+        );
+        assert_eq!(
+            other.iter().collect::<Vec<_>>(),
+            [(incl(15, 19), &'b'), (incl(30, 39), &'c')]
+        );
+        assert_eq!(tree.len(), 2);
+        assert_eq!(other.len(), 2);
+    }
+
+    #[test]
+    fn split_off_before_and_after_all() {
+        let mut tree = numbered(100);
+        let other = tree.split_off(nz(1000)).unwrap();
+        other.assert_valid();
+        assert!(other.is_empty());
+        assert_eq!(tree.len(), 100);
+
+        let other = tree.split_off(nz(1)).unwrap();
+        tree.assert_valid();
+        other.assert_valid();
+        assert!(tree.is_empty());
+        assert_eq!(tree.len(), 0);
+        assert_eq!(other.len(), 100);
+        assert!(other.values().copied().eq(0..100));
+    }
+
+    #[test]
+    fn new() {
+        let mut tree = RangeTree::new();
+        assert!(tree.is_empty());
+        tree.insert(incl(1, 9), 'a').unwrap();
+        tree.insert(incl(20, 29), 'b').unwrap();
+        tree.assert_valid();
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree.get(nz(5)), Some(&'a'));
+        assert_eq!(tree.get(nz(25)), Some(&'b'));
+        assert_eq!(tree.get(nz(15)), None);
+
+        let tree: RangeTree<NonZeroU64, u32> = RangeTree::new_in(alloc::alloc::Global);
+        assert!(tree.is_empty());
+        tree.assert_valid();
+    }
+}
@@ -74,18 +74,18 @@ fn overlap() {
 
     assert!(matches!(
         tree.insert(nonzero!(100)..=nonzero!(110), 2),
-        Err(OverlapError)
+        Err(OverlapError { .. })
     ));
     assert!(matches!(
         tree.insert(nonzero!(199)..=nonzero!(201), 2),
-        Err(OverlapError)
+        Err(OverlapError { .. })
     ));
     assert!(matches!(
         tree.insert(nonzero!(110)..=nonzero!(190), 2),
-        Err(OverlapError)
+        Err(OverlapError { .. })
     ));
     assert!(matches!(
         tree.insert(nonzero!(110)..=nonzero!(301), 1),
-        Err(OverlapError)
+        Err(OverlapError { .. })
     ));
 }
@@ -5,6 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use core::mem;
 use core::pin::Pin;
 use core::ptr::NonNull;
 
@@ -79,6 +80,35 @@ where
     pub fn remove(self) -> T::Handle {
         self._tree.remove_internal(self.node)
     }
+    /// Replaces the entry's node with `element`, returning the owned handle of the old node.
+    ///
+    /// `element` takes over the old node's position in the tree, so unlike removing and
+    /// re-inserting this never rebalances the tree. None of the [`Linked`] hooks are invoked
+    /// either, so any augmented data `element` carries must match that of the node it replaces.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `element` is already linked to a different intrusive collection. With debug
+    /// assertions enabled, also panics if the key of `element` differs from the entry's key.
+    pub fn replace(&mut self, element: T::Handle) -> T::Handle {
+        let new = T::into_ptr(element);
+        unsafe {
+            assert!(!T::links(new).as_ref().is_linked());
+            debug_assert!(
+                new.as_ref().get_key() == self.node.as_ref().get_key(),
+                "replacement must have the same key"
+            );
+        }
+
+        let old = mem::replace(&mut self.node, new);
+        self._tree.swap_in_node_at(old, new);
+
+        unsafe {
+            // `swap_in_node_at` leaves the rank behind, reset it so the node can be reinserted
+            T::links(old).as_mut().unlink();
+            T::from_ptr(old)
+        }
+    }
     pub fn peek_next(&self) -> Option<&'a T> {
         let node = utils::next(self.node)?;
         unsafe { Some(node.as_ref()) }
@@ -0,0 +1,53 @@
+// Copyright 2023-Present Jonas Kruckenberg
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::fmt;
+
+use crate::Side;
+
+/// A violated tree invariant, as reported by [`WAVLTree::check_valid`](crate::WAVLTree::check_valid).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeError {
+    /// A node's parent or child link points to the node itself.
+    SelfLink,
+    /// Two of a node's links (parent, left or right child) point to the same node.
+    DuplicateLink,
+    /// The root node has a parent.
+    RootHasParent,
+    /// A node's child doesn't link back to the node as its parent.
+    ParentMismatch(Side),
+    /// A node's left child isn't less, or its right child isn't greater than the node.
+    Ordering(Side),
+    /// The rank difference between a node and its parent isn't 1 or 2.
+    RankDifference { parent_rank: usize, rank: usize },
+    /// A leaf node doesn't have rank 0.
+    LeafRank(usize),
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::SelfLink => write!(f, "node links to itself"),
+            TreeError::DuplicateLink => write!(f, "node links to the same node twice"),
+            TreeError::RootHasParent => write!(f, "root node has a parent"),
+            TreeError::ParentMismatch(side) => {
+                write!(f, "node's {side} child doesn't link back to it")
+            }
+            TreeError::Ordering(Side::Left) => write!(f, "left subtree is not less than node"),
+            TreeError::Ordering(Side::Right) => {
+                write!(f, "right subtree is not greater than node")
+            }
+            TreeError::RankDifference { parent_rank, rank } => write!(
+                f,
+                "rank difference must be 1 or 2, but node has rank {rank} and parent rank {parent_rank}"
+            ),
+            TreeError::LeafRank(rank) => write!(f, "leaf must be rank 0, but was {rank}"),
+        }
+    }
+}
+
+impl core::error::Error for TreeError {}
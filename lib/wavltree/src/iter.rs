@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use core::iter::FusedIterator;
+use core::marker::PhantomData;
 use core::pin::Pin;
 
 use crate::{Link, Linked, WAVLTree, utils};
@@ -67,6 +68,44 @@ where
 }
 impl<'a, T> FusedIterator for Iter<'a, T> where T: Linked + ?Sized + 'a {}
 
+/// An iterator over the entries of a [`WAVLTree`] paired with their keys, created by
+/// [`WAVLTree::iter_keys`].
+pub struct IterKeys<'a, T: Linked + ?Sized> {
+    pub(crate) inner: Iter<'a, T>,
+}
+impl<'a, T> Clone for IterKeys<'a, T>
+where
+    T: Linked + ?Sized,
+{
+    #[inline]
+    fn clone(&self) -> IterKeys<'a, T> {
+        IterKeys {
+            inner: self.inner.clone(),
+        }
+    }
+}
+impl<'a, T> Iterator for IterKeys<'a, T>
+where
+    T: Linked + ?Sized + 'a,
+{
+    type Item = (&'a T::Key, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (entry.get_key(), entry))
+    }
+}
+impl<'a, T> DoubleEndedIterator for IterKeys<'a, T>
+where
+    T: Linked + ?Sized + 'a,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|entry| (entry.get_key(), entry))
+    }
+}
+impl<'a, T> FusedIterator for IterKeys<'a, T> where T: Linked + ?Sized + 'a {}
+
 /// An iterator over mutable references to the entries of a [`WAVLTree`].
 pub struct IterMut<'a, T>
 where
@@ -207,3 +246,33 @@ where
     }
 }
 impl<T> FusedIterator for IntoIter<T> where T: Linked + ?Sized {}
+
+/// A draining iterator over the entries of a [`WAVLTree`], created by [`WAVLTree::drain`].
+pub struct Drain<'a, T>
+where
+    T: Linked + ?Sized,
+{
+    pub(crate) inner: IntoIter<T>,
+    pub(crate) _tree: PhantomData<&'a mut WAVLTree<T>>,
+}
+impl<T> Iterator for Drain<'_, T>
+where
+    T: Linked + ?Sized,
+{
+    type Item = T::Handle;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+impl<T> DoubleEndedIterator for Drain<'_, T>
+where
+    T: Linked + ?Sized,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+impl<T> FusedIterator for Drain<'_, T> where T: Linked + ?Sized {}
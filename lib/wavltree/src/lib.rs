@@ -136,13 +136,14 @@ mod cursor;
 #[cfg(feature = "dot")]
 mod dot;
 mod entry;
+mod error;
 mod iter;
 mod utils;
 
 use core::borrow::Borrow;
 use core::cell::UnsafeCell;
 use core::cmp::Ordering;
-use core::marker::PhantomPinned;
+use core::marker::{PhantomData, PhantomPinned};
 use core::ops::{Bound, RangeBounds};
 use core::pin::Pin;
 use core::ptr::NonNull;
@@ -150,11 +151,12 @@ use core::{fmt, mem, ptr};
 
 #[cfg(feature = "dot")]
 pub use dot::Dot;
-pub use iter::{IntoIter, Iter, IterMut};
+pub use iter::{Drain, IntoIter, Iter, IterKeys, IterMut};
 pub use utils::Side;
 
 pub use crate::cursor::{Cursor, CursorMut};
 pub use crate::entry::{Entry, OccupiedEntry, VacantEntry};
+pub use crate::error::TreeError;
 use crate::utils::get_sibling;
 
 /// Trait implemented by types which can be members of an [intrusive WAVL tree][WAVLTree].
@@ -489,6 +491,21 @@ where
         self.size() == 0
     }
 
+    /// Returns the number of nodes on the longest path from the root to a leaf, `0` for an empty
+    /// tree.
+    ///
+    /// This is meant for diagnostics and walks the entire tree, so it runs in *O*(*n*) time.
+    pub fn height(&self) -> usize {
+        fn height_inner<T: Linked + ?Sized>(node: Link<T>) -> usize {
+            node.map_or(0, |node| {
+                let links = unsafe { T::links(node).as_ref() };
+                1 + height_inner(links.left()).max(height_inner(links.right()))
+            })
+        }
+
+        height_inner(self.root)
+    }
+
     /// Returns a double-ended iterator over a sub-range of entries in the tree. The simplest way is
     /// to use the range syntax `min..max`, thus `range(min..max)` will yield elements from min (inclusive)
     /// to max (exclusive). The range may also be entered as `(Bound<T>, Bound<T>)`, so for example
@@ -589,6 +606,27 @@ where
         }
     }
 
+    /// Returns a reference to the entry with the given key, or `None` if no such entry exists.
+    pub fn get<Q>(&self, key: &Q) -> Option<&T>
+    where
+        <T as Linked>::Key: Borrow<Q>,
+        Q: Ord,
+    {
+        let (node, _) = self.find_internal(key);
+        node.map(|node| unsafe { node.as_ref() })
+    }
+
+    /// Returns a pinned mutable reference to the entry with the given key, or `None` if no such
+    /// entry exists.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<Pin<&mut T>>
+    where
+        <T as Linked>::Key: Borrow<Q>,
+        Q: Ord,
+    {
+        let (node, _) = self.find_internal(key);
+        node.map(|mut node| unsafe { Pin::new_unchecked(node.as_mut()) })
+    }
+
     /// Returns a cursor to the root of the tree.
     #[inline]
     pub fn root(&self) -> Cursor<'_, T> {
@@ -678,8 +716,28 @@ where
     ///
     /// # Panics
     ///
-    /// Panics if the new entry is already linked to a different intrusive collection.
+    /// Panics if the new entry is already linked to a different intrusive collection or an entry
+    /// with the same key is already part of the tree. See [`WAVLTree::try_insert`] for a
+    /// non-panicking alternative to the latter.
     pub fn insert(&mut self, element: T::Handle) -> Pin<&mut T> {
+        match self.try_insert(element) {
+            Ok(entry) => entry,
+            Err(_) => panic!("already inserted"),
+        }
+    }
+
+    /// Insert a new entry into the `WAVLTree`, unless an entry with the same key is already part
+    /// of the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handle back, leaving the tree unchanged, if an entry with the same key is
+    /// already part of the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new entry is already linked to a different intrusive collection.
+    pub fn try_insert(&mut self, element: T::Handle) -> Result<Pin<&mut T>, T::Handle> {
         unsafe {
             let mut ptr = T::into_ptr(element);
             debug_assert_ne!(self.root, Some(ptr));
@@ -694,7 +752,8 @@ where
                     let curr_links = T::links(curr).as_mut();
 
                     let side = match key.cmp(curr.as_ref().get_key().borrow()) {
-                        Ordering::Equal => panic!("already inserted"),
+                        // Nothing has been modified yet, so we can simply hand the element back.
+                        Ordering::Equal => return Err(T::from_ptr(ptr)),
                         Ordering::Less => Side::Left,
                         Ordering::Greater => Side::Right,
                     };
@@ -720,7 +779,7 @@ where
                 self.balance_after_insert(ptr);
             }
 
-            Pin::new_unchecked(ptr.as_mut())
+            Ok(Pin::new_unchecked(ptr.as_mut()))
         }
     }
 
@@ -735,7 +794,6 @@ where
         Q: Ord,
     {
         let ptr = self.find_internal(key).0?;
-        self.size -= 1;
         Some(self.remove_internal(ptr))
     }
 
@@ -791,6 +849,30 @@ where
         }
     }
 
+    /// Returns a reference to the entry with the greatest key less than or equal to `key`, or `None`
+    /// if all keys are greater.
+    #[inline]
+    pub fn floor<Q>(&self, key: &Q) -> Option<&T>
+    where
+        <T as Linked>::Key: Borrow<Q>,
+        Q: Ord,
+    {
+        let node = self.find_upper_bound(Bound::Included(key))?;
+        Some(unsafe { node.as_ref() })
+    }
+
+    /// Returns a reference to the entry with the smallest key greater than or equal to `key`, or
+    /// `None` if all keys are smaller.
+    #[inline]
+    pub fn ceil<Q>(&self, key: &Q) -> Option<&T>
+    where
+        <T as Linked>::Key: Borrow<Q>,
+        Q: Ord,
+    {
+        let node = self.find_lower_bound(Bound::Included(key))?;
+        Some(unsafe { node.as_ref() })
+    }
+
     /// Gets an iterator over the entries in the tree, sorted by their key.
     pub fn iter(&self) -> Iter<'_, T> {
         Iter {
@@ -800,6 +882,13 @@ where
         }
     }
 
+    /// Gets an iterator over the entries in the tree paired with their keys, sorted by key.
+    ///
+    /// This is equivalent to `tree.iter().map(|entry| (entry.get_key(), entry))`.
+    pub fn iter_keys(&self) -> IterKeys<'_, T> {
+        IterKeys { inner: self.iter() }
+    }
+
     /// Gets a mutable iterator over the entries in the tree, sorted by their key.
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         IterMut {
@@ -816,6 +905,7 @@ where
         if let Some(root) = self.root.take() {
             self.clear_inner(root);
         }
+        self.size = 0;
     }
 
     #[inline]
@@ -837,6 +927,34 @@ where
         }
     }
 
+    /// Removes all elements from the tree without converting them back into handles.
+    ///
+    /// Every node is unlinked and its raw pointer handed to `f`, in unspecified order, instead of
+    /// being passed to [`Linked::from_ptr`] and dropped like [`clear`](Self::clear) does. This is
+    /// useful when nodes are owned elsewhere, e.g. `'static` structs that should be moved to
+    /// another collection. Note that for owning handles like `Box` this leaks the nodes unless `f`
+    /// reclaims them.
+    pub fn clear_with(&mut self, mut f: impl FnMut(NonNull<T>)) {
+        if let Some(root) = self.root.take() {
+            Self::clear_with_inner(root, &mut f);
+        }
+        self.size = 0;
+    }
+
+    fn clear_with_inner(node: NonNull<T>, f: &mut impl FnMut(NonNull<T>)) {
+        unsafe {
+            let node_links = T::links(node).as_mut();
+            if let Some(left) = node_links.left() {
+                Self::clear_with_inner(left, f);
+            }
+            if let Some(right) = node_links.right() {
+                Self::clear_with_inner(right, f);
+            }
+            node_links.unlink();
+        }
+        f(node);
+    }
+
     /// Takes all the elements out of the `WAVLTree`, leaving it empty. The taken elements are returned as a new `WAVLTree`.
     #[inline]
     pub fn take(&mut self) -> Self {
@@ -845,9 +963,52 @@ where
             size: self.size,
         };
         self.root = None;
+        self.size = 0;
         tree
     }
 
+    /// Removes all entries from the tree, returning their owned handles as an iterator sorted by
+    /// key.
+    ///
+    /// The tree is empty as soon as this method returns. Entries not consumed by the iterator are
+    /// unlinked and dropped when the iterator is dropped, just like [`WAVLTree::clear`] would.
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain {
+            inner: self.take().into_iter(),
+            _tree: PhantomData,
+        }
+    }
+
+    /// Moves all entries from `other` into `self`, leaving `other` empty.
+    ///
+    /// Entries are moved one by one in key order, so this takes `O(m log(n + m))` time where `m` is
+    /// the size of `other`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry of `other` has the same key as an entry already in `self`. See
+    /// [`WAVLTree::try_append`] for a non-panicking alternative.
+    pub fn append(&mut self, other: &mut Self) {
+        if self.try_append(other).is_err() {
+            panic!("already inserted");
+        }
+    }
+
+    /// Moves all entries from `other` into `self`, stopping at the first key collision.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handle of the first entry of `other` whose key is already part of `self`. That
+    /// entry has been unlinked from `other`, all entries with smaller keys have already been moved
+    /// into `self` and all entries with larger keys remain in `other`.
+    pub fn try_append(&mut self, other: &mut Self) -> Result<(), T::Handle> {
+        while let Some(handle) = other.front_mut().remove() {
+            self.try_insert(handle)?;
+        }
+        Ok(())
+    }
+
     /// Asserts as many of the tree's invariants as possible.
     ///
     /// Note that with debug assertions enabled, this includes validating the WAVL rank-balancing
@@ -920,6 +1081,72 @@ where
         }
     }
 
+    /// Checks the same invariants as [`assert_valid`](Self::assert_valid), but returns the first
+    /// violation instead of panicking.
+    ///
+    /// Additionally, this checks that every child links back to its parent. Like `assert_valid`,
+    /// the WAVL rank-balancing rules are only validated with debug assertions enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`TreeError`] describing the first invariant found to be violated.
+    pub fn check_valid(&self) -> Result<(), TreeError> {
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+
+        unsafe {
+            let root_links = T::links(root).as_ref();
+            if root_links.parent().is_some() {
+                return Err(TreeError::RootHasParent);
+            }
+
+            Self::check_valid_inner(root)
+        }
+    }
+
+    fn check_valid_inner(node: NonNull<T>) -> Result<(), TreeError> {
+        unsafe {
+            let node_links = T::links(node).as_ref();
+            node_links.check_valid()?;
+
+            for side in [Side::Left, Side::Right] {
+                let Some(child) = node_links.child(side) else {
+                    continue;
+                };
+                let child_links = T::links(child).as_ref();
+
+                if child_links.parent() != Some(node) {
+                    return Err(TreeError::ParentMismatch(side));
+                }
+
+                let ordered = match side {
+                    Side::Left => child.as_ref().get_key() < node.as_ref().get_key(),
+                    Side::Right => child.as_ref().get_key() > node.as_ref().get_key(),
+                };
+                if !ordered {
+                    return Err(TreeError::Ordering(side));
+                }
+
+                // We can only check the WAVL rule if we track the rank, which we only do in debug builds
+                #[cfg(debug_assertions)]
+                {
+                    let (parent_rank, rank) = (node_links.rank(), child_links.rank());
+                    if !matches!(parent_rank.checked_sub(rank), Some(1 | 2)) {
+                        return Err(TreeError::RankDifference { parent_rank, rank });
+                    }
+                    if child_links.is_leaf() && rank != 0 {
+                        return Err(TreeError::LeafRank(rank));
+                    }
+                }
+
+                Self::check_valid_inner(child)?;
+            }
+
+            Ok(())
+        }
+    }
+
     #[cfg(feature = "dot")]
     pub fn dot(&self) -> Dot<'_, T> {
         Dot { tree: self }
@@ -1073,6 +1300,8 @@ where
             );
         }
 
+        self.size -= 1;
+
         // unlink the node from the tree and return
         unsafe {
             node_links.unlink();
@@ -1526,6 +1755,10 @@ impl<T: ?Sized> Links<T> {
         self.inner.get_mut().left = None;
         self.inner.get_mut().right = None;
         self.inner.get_mut().rank_parity = false;
+        #[cfg(debug_assertions)]
+        {
+            self.inner.get_mut().rank = 0;
+        }
     }
 
     #[inline]
@@ -1622,6 +1855,29 @@ impl<T: ?Sized> Links<T> {
         }
     }
 
+    /// Checks that none of this node's links point to itself or to the same node twice.
+    fn check_valid(&self) -> Result<(), TreeError>
+    where
+        T: Linked,
+    {
+        let this = NonNull::from(self);
+        let links = [self.parent(), self.left(), self.right()]
+            .map(|link| link.map(|node| unsafe { T::links(node) }));
+
+        if links.contains(&Some(this)) {
+            return Err(TreeError::SelfLink);
+        }
+
+        let [parent, left, right] = links;
+        if (parent.is_some() && (parent == left || parent == right))
+            || (left.is_some() && left == right)
+        {
+            return Err(TreeError::DuplicateLink);
+        }
+
+        Ok(())
+    }
+
     /// Asserts as many invariants about this particular node as possible.
     ///
     /// # Panics
@@ -1696,6 +1952,7 @@ mod tests {
     #[derive(Default)]
     struct TestEntry {
         value: usize,
+        data: usize,
         links: Links<Self>,
     }
     impl TestEntry {
@@ -1874,4 +2131,431 @@ mod tests {
         assert_eq!(iter.next_back().unwrap().value, 1000);
         assert_eq!(iter.next_back().unwrap().value, 500);
     }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn height() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        assert_eq!(tree.height(), 0);
+
+        tree.insert(Box::pin(TestEntry::new(0)));
+        assert_eq!(tree.height(), 1);
+
+        for i in 1..1000 {
+            tree.insert(Box::pin(TestEntry::new(i)));
+        }
+
+        // Without deletions a WAVL tree is an AVL tree, whose height is bounded by
+        // `1.4405 * log2(n + 2) - 0.3277`.
+        let n = tree.size() as f64;
+        let bound = 1.4405_f64 * (n + 2.0).log2() - 0.3277_f64;
+        let height = tree.height();
+        assert!(
+            (height as f64) < bound,
+            "height {height} exceeds the WAVL bound {bound}"
+        );
+        // ...and can't be lower than that of a perfectly balanced tree
+        assert!(height >= 10);
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn get() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        assert!(tree.get(&1000).is_none());
+
+        tree.insert(Box::pin(TestEntry::new(1000)));
+        tree.insert(Box::pin(TestEntry::new(3000)));
+        tree.insert(Box::pin(TestEntry::new(500)));
+
+        assert_eq!(tree.get(&1000).unwrap().value, 1000);
+        assert_eq!(tree.get(&500).unwrap().value, 500);
+        assert!(tree.get(&2000).is_none());
+        assert!(tree.get(&0).is_none());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn get_mut() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+
+        tree.insert(Box::pin(TestEntry::new(1000)));
+        tree.insert(Box::pin(TestEntry::new(3000)));
+
+        assert!(tree.get_mut(&2000).is_none());
+
+        let entry = tree.get_mut(&3000).unwrap();
+        // Safety: we only touch a plain field, nothing is moved out
+        unsafe { entry.get_unchecked_mut().data = 42 };
+
+        assert_eq!(tree.get(&3000).unwrap().data, 42);
+        assert_eq!(tree.get(&1000).unwrap().data, 0);
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn try_insert() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+
+        for value in [1000, 3000, 500] {
+            let entry = tree.try_insert(Box::pin(TestEntry::new(value))).unwrap();
+            assert_eq!(entry.value, value);
+        }
+
+        let mut duplicate = Box::pin(TestEntry::new(3000));
+        // Safety: we only touch a plain field, nothing is moved out
+        unsafe { duplicate.as_mut().get_unchecked_mut().data = 42 };
+        let duplicate_ptr: *const TestEntry = &*duplicate;
+
+        let returned = tree.try_insert(duplicate).unwrap_err();
+        // the very same handle comes back, unlinked and untouched
+        assert!(core::ptr::eq(&*returned, duplicate_ptr));
+        assert_eq!(returned.data, 42);
+        assert!(!returned.links.is_linked());
+
+        assert_eq!(tree.size(), 3);
+        assert_eq!(tree.get(&3000).unwrap().data, 0);
+        tree.assert_valid("after try_insert");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn append() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        let mut other: WAVLTree<TestEntry> = WAVLTree::new();
+
+        for value in [10, 30, 50, 70] {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+        for value in [60, 0, 40, 20, 80] {
+            other.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        tree.append(&mut other);
+
+        assert!(other.is_empty());
+        assert_eq!(tree.size(), 9);
+        assert!(
+            tree.iter()
+                .map(|entry| entry.value)
+                .eq([0, 10, 20, 30, 40, 50, 60, 70, 80])
+        );
+        tree.assert_valid("after append");
+        other.assert_valid("after append");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn try_append_collision() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        let mut other: WAVLTree<TestEntry> = WAVLTree::new();
+
+        for value in [10, 30] {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+        for value in [0, 20, 30, 40] {
+            other.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        let returned = tree.try_append(&mut other).unwrap_err();
+        assert_eq!(returned.value, 30);
+        assert!(!returned.links.is_linked());
+
+        // entries before the collision have been moved, the ones after it are left behind
+        assert!(tree.iter().map(|entry| entry.value).eq([0, 10, 20, 30]));
+        assert!(other.iter().map(|entry| entry.value).eq([40]));
+        tree.assert_valid("after try_append");
+        other.assert_valid("after try_append");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    #[should_panic(expected = "already inserted")]
+    fn append_collision() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        let mut other: WAVLTree<TestEntry> = WAVLTree::new();
+
+        tree.insert(Box::pin(TestEntry::new(10)));
+        other.insert(Box::pin(TestEntry::new(10)));
+
+        tree.append(&mut other);
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn check_valid() {
+        fn links(node: NonNull<TestEntry>) -> &'static mut Links<TestEntry> {
+            unsafe { TestEntry::links(node).as_mut() }
+        }
+
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        assert_eq!(tree.check_valid(), Ok(()));
+        for value in [2, 1, 3] {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+        assert_eq!(tree.check_valid(), Ok(()));
+
+        let root = tree.root.unwrap();
+        let left = links(root).left().unwrap();
+        let right = links(root).right().unwrap();
+
+        // Every corruption below is undone again, so dropping the tree doesn't trip over it.
+        unsafe { (*left.as_ptr()).value = 5 };
+        assert_eq!(tree.check_valid(), Err(TreeError::Ordering(Side::Left)));
+        unsafe { (*left.as_ptr()).value = 1 };
+
+        links(right).replace_parent(None);
+        assert_eq!(
+            tree.check_valid(),
+            Err(TreeError::ParentMismatch(Side::Right))
+        );
+        links(right).replace_parent(Some(root));
+
+        links(root).replace_parent(Some(left));
+        assert_eq!(tree.check_valid(), Err(TreeError::RootHasParent));
+        links(root).replace_parent(None);
+
+        links(left).replace_left(Some(left));
+        assert_eq!(tree.check_valid(), Err(TreeError::SelfLink));
+        links(left).replace_left(None);
+
+        links(root).replace_right(Some(left));
+        assert_eq!(tree.check_valid(), Err(TreeError::DuplicateLink));
+        links(root).replace_right(Some(right));
+
+        #[cfg(debug_assertions)]
+        {
+            links(root).double_promote();
+            assert_eq!(
+                tree.check_valid(),
+                Err(TreeError::RankDifference {
+                    parent_rank: 3,
+                    rank: 0
+                })
+            );
+            links(root).double_demote();
+
+            links(root).promote();
+            links(left).promote();
+            assert_eq!(tree.check_valid(), Err(TreeError::LeafRank(1)));
+            links(left).demote();
+            links(root).demote();
+        }
+
+        assert_eq!(tree.check_valid(), Ok(()));
+        tree.assert_valid("after restoring");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn clear_with() {
+        #[derive(Default)]
+        struct StaticEntry {
+            value: usize,
+            links: Links<Self>,
+        }
+        unsafe impl Linked for StaticEntry {
+            type Handle = NonNull<Self>;
+            type Key = usize;
+
+            fn into_ptr(handle: Self::Handle) -> NonNull<Self> {
+                handle
+            }
+
+            unsafe fn from_ptr(ptr: NonNull<Self>) -> Self::Handle {
+                ptr
+            }
+
+            unsafe fn links(ptr: NonNull<Self>) -> NonNull<Links<Self>> {
+                ptr.map_addr(|addr| {
+                    let offset = offset_of!(Self, links);
+                    addr.checked_add(offset).unwrap()
+                })
+                .cast()
+            }
+
+            fn get_key(&self) -> &Self::Key {
+                &self.value
+            }
+        }
+
+        let mut entries: [StaticEntry; 10] = Default::default();
+        let mut tree: WAVLTree<StaticEntry> = WAVLTree::new();
+        for (value, entry) in entries.iter_mut().enumerate() {
+            entry.value = value;
+            tree.insert(NonNull::from(entry));
+        }
+
+        let mut cleared = Vec::new();
+        tree.clear_with(|ptr| cleared.push(ptr));
+        assert!(tree.is_empty());
+        assert!(tree.root.is_none());
+        tree.assert_valid("after clear_with");
+
+        // every node was handed out exactly once, unlinked but otherwise untouched
+        let mut values: Vec<_> = cleared
+            .iter()
+            .map(|ptr| unsafe { ptr.as_ref() }.value)
+            .collect();
+        values.sort_unstable();
+        assert!(values.into_iter().eq(0..10));
+        assert!(entries.iter().all(|entry| !entry.links.is_linked()));
+
+        // so the nodes can be linked into another tree right away
+        let mut other: WAVLTree<StaticEntry> = WAVLTree::new();
+        for ptr in cleared {
+            other.insert(ptr);
+        }
+        other.assert_valid("after reinsertion");
+        assert_eq!(other.iter().count(), 10);
+        other.clear_with(drop);
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn floor_ceil() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        for value in [40, 10, 30, 20] {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        // exact matches
+        assert_eq!(tree.floor(&20).map(|entry| entry.value), Some(20));
+        assert_eq!(tree.ceil(&20).map(|entry| entry.value), Some(20));
+
+        // between keys
+        assert_eq!(tree.floor(&25).map(|entry| entry.value), Some(20));
+        assert_eq!(tree.ceil(&25).map(|entry| entry.value), Some(30));
+
+        // out of range
+        assert!(tree.floor(&5).is_none());
+        assert_eq!(tree.ceil(&5).map(|entry| entry.value), Some(10));
+        assert_eq!(tree.floor(&50).map(|entry| entry.value), Some(40));
+        assert!(tree.ceil(&50).is_none());
+
+        let empty: WAVLTree<TestEntry> = WAVLTree::new();
+        assert!(empty.floor(&0).is_none());
+        assert!(empty.ceil(&0).is_none());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn iter_keys() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        for value in [40, 10, 30, 0, 20] {
+            let mut entry = TestEntry::new(value);
+            entry.data = value * 2;
+            tree.insert(Box::pin(entry));
+        }
+
+        let pairs: Vec<(usize, usize)> = tree
+            .iter_keys()
+            .map(|(key, entry)| (*key, entry.data))
+            .collect();
+        assert_eq!(pairs, [(0, 0), (10, 20), (20, 40), (30, 60), (40, 80)]);
+
+        let (key, entry) = tree.iter_keys().next_back().unwrap();
+        assert_eq!(*key, 40);
+        assert_eq!(entry.value, 40);
+
+        assert!(WAVLTree::<TestEntry>::new().iter_keys().next().is_none());
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn drain() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        for value in [40, 10, 30, 0, 20] {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        let mut drain = tree.drain();
+        let first = drain.next().unwrap();
+        let last = drain.next_back().unwrap();
+        let rest: Vec<_> = drain.collect();
+
+        assert_eq!(first.value, 0);
+        assert_eq!(last.value, 40);
+        assert!(rest.iter().map(|entry| entry.value).eq([10, 20, 30]));
+        assert!(
+            [&first, &last]
+                .into_iter()
+                .chain(&rest)
+                .all(|entry| !entry.links.is_linked())
+        );
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.iter().count(), 0);
+        tree.assert_valid("after drain");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn drain_dropped_early() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        for value in 0..50 {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        let taken: Vec<_> = tree.drain().take(3).collect();
+        assert!(taken.iter().map(|entry| entry.value).eq([0, 1, 2]));
+
+        // the remaining entries were dropped along with the iterator
+        assert!(tree.is_empty());
+        tree.assert_valid("after drain");
+
+        // the tree is still usable afterwards
+        tree.insert(Box::pin(TestEntry::new(7)));
+        assert_eq!(tree.size(), 1);
+        tree.assert_valid("after reinsert");
+    }
+
+    #[cfg(not(target_os = "none"))]
+    #[test]
+    fn entry_replace() {
+        let mut tree: WAVLTree<TestEntry> = WAVLTree::new();
+        for value in 1..=15 {
+            tree.insert(Box::pin(TestEntry::new(value)));
+        }
+
+        // Safety: the tree is not modified while we look at its structure
+        let (root, internal, leaf) = unsafe {
+            let root = tree.root.unwrap();
+            let internal = TestEntry::links(root).as_ref().left().unwrap();
+            let leaf = utils::find_minimum(root);
+            assert!(!TestEntry::links(internal).as_ref().is_leaf());
+            assert!(TestEntry::links(leaf).as_ref().is_leaf());
+            (
+                root.as_ref().value,
+                internal.as_ref().value,
+                leaf.as_ref().value,
+            )
+        };
+
+        for value in [leaf, internal, root] {
+            let mut replacement = Box::pin(TestEntry::new(value));
+            // Safety: we only touch a plain field, nothing is moved out
+            unsafe { replacement.as_mut().get_unchecked_mut().data = 42 };
+
+            let Entry::Occupied(mut entry) = tree.entry(&value) else {
+                panic!("entry for {value} should be occupied");
+            };
+            let old = entry.replace(replacement);
+            assert_eq!(entry.get().data, 42);
+
+            assert_eq!(old.value, value);
+            assert_eq!(old.data, 0);
+            assert!(!old.links.is_linked());
+
+            assert_eq!(tree.get(&value).unwrap().data, 42);
+            assert_eq!(tree.size(), 15);
+            assert!(tree.iter().map(|entry| entry.value).eq(1..=15));
+            tree.assert_valid("after replace");
+
+            // the old node can be reused
+            let mut other: WAVLTree<TestEntry> = WAVLTree::new();
+            other.insert(old);
+            other.assert_valid("after reinserting replaced node");
+        }
+    }
 }
@@ -10,7 +10,7 @@ use core::{fmt, ptr};
 
 use crate::{Link, Linked};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Side {
     Left,
     Right,
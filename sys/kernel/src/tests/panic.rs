@@ -8,9 +8,13 @@
 //! Exercises the panic/unwind runtime end-to-end: the real `#[panic_handler]`,
 //! unwinder, and per-CPU accounting are all live under the in-kernel harness.
 
+use alloc::boxed::Box;
+use alloc::format;
 use core::cell::Cell;
 use core::panic::AssertUnwindSafe;
 
+use panic_unwind::PanicFormat;
+
 /// A `panic!` whose unwind runs a `Drop` that itself catches an inner `panic!`
 /// must deliver the inner panic to the drop's `catch_unwind` and still deliver
 /// the outer panic to the outer one — nesting is strictly LIFO.
@@ -49,3 +53,163 @@ async fn resume_reraises_to_outer_catch() {
         "resumed unwind was not caught at the outer frame"
     );
 }
+
+/// `catch_unwind_with_info` must hand back the formatted message and the
+/// location of the `panic!` that was caught.
+#[test::test]
+async fn catch_unwind_with_info_recovers_message_and_location() {
+    let line = line!() + 1;
+    let info = panic_unwind::catch_unwind_with_info(|| panic!("boom {}", 42))
+        .err()
+        .expect("panic was not caught");
+
+    assert_eq!(info.message(), "boom 42");
+    let location = info.location().expect("panic has no location");
+    assert_eq!(location.file(), file!());
+    assert_eq!(location.line(), line);
+}
+
+/// A panic caught inside a `Drop` impl while another panic unwinds must not
+/// clobber the info of the outer panic.
+#[test::test]
+async fn catch_unwind_with_info_nested_during_drop() {
+    struct Guard;
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let inner = panic_unwind::catch_unwind_with_info(|| panic!("inner"))
+                .err()
+                .expect("inner panic was not caught");
+            assert_eq!(inner.message(), "inner");
+        }
+    }
+
+    let outer = panic_unwind::catch_unwind_with_info(|| {
+        let _guard = Guard;
+        panic!("outer");
+    })
+    .err()
+    .expect("outer panic was not caught");
+
+    assert_eq!(outer.message(), "outer");
+}
+
+/// `panic_count` must count every in-flight unwind, including ones raised from
+/// a `Drop` impl of an outer unwind, and drop back to zero once caught.
+#[test::test]
+async fn panic_count_tracks_nested_unwinds() {
+    struct Observe<'a>(&'a Cell<usize>);
+    impl Drop for Observe<'_> {
+        fn drop(&mut self) {
+            self.0.set(panic_unwind::panic_count());
+        }
+    }
+
+    struct Nest<'a>(&'a Cell<usize>);
+    impl Drop for Nest<'_> {
+        fn drop(&mut self) {
+            let inner = panic_unwind::catch_unwind(AssertUnwindSafe(|| {
+                let _observe = Observe(self.0);
+                panic!("inner");
+            }));
+            assert!(inner.is_err(), "inner panic was not caught");
+        }
+    }
+
+    assert_eq!(panic_unwind::panic_count(), 0);
+
+    let outer_count = Cell::new(0);
+    let inner_count = Cell::new(0);
+    let outer = panic_unwind::catch_unwind(AssertUnwindSafe(|| {
+        let _nest = Nest(&inner_count);
+        let _observe = Observe(&outer_count);
+        panic!("outer");
+    }));
+
+    assert!(outer.is_err(), "outer panic was not caught");
+    assert_eq!(outer_count.get(), 1);
+    assert_eq!(inner_count.get(), 2);
+    assert_eq!(panic_unwind::panic_count(), 0);
+}
+
+/// An `AbortOnDrop` guard created while an unwind is already in flight must
+/// only abort on panics raised after it was created, not on the one being
+/// unwound. (The abort path itself terminates the kernel and can't be tested
+/// here.)
+#[test::test]
+async fn abort_on_unwind_inside_unwinding_drop() {
+    struct Guard<'a>(&'a Cell<u32>);
+    impl Drop for Guard<'_> {
+        fn drop(&mut self) {
+            self.0.set(panic_unwind::abort_on_unwind(|| 42));
+        }
+    }
+
+    let value = Cell::new(0);
+    let outer = panic_unwind::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = Guard(&value);
+        panic!("outer");
+    }));
+
+    assert!(outer.is_err(), "outer panic was not caught");
+    assert_eq!(value.get(), 42);
+}
+
+/// `with_abort_on_panic` must disable unwinding for exactly the duration of
+/// the (possibly nested) scope, panics outside of it still unwind normally.
+/// (The abort path itself terminates the kernel and can't be tested here.)
+#[test::test]
+async fn with_abort_on_panic_is_scoped() {
+    assert!(panic_unwind::can_unwind());
+
+    let value = panic_unwind::with_abort_on_panic(|| {
+        assert!(!panic_unwind::can_unwind());
+        panic_unwind::with_abort_on_panic(|| assert!(!panic_unwind::can_unwind()));
+        assert!(!panic_unwind::can_unwind());
+        42
+    });
+    assert_eq!(value, 42);
+
+    assert!(panic_unwind::can_unwind());
+    let caught = panic_unwind::catch_unwind(|| panic!("outside the scope"));
+    assert!(caught.is_err(), "panic outside the scope was not caught");
+}
+
+/// With an emergency buffer set, the handler must format the report into it
+/// (and leave it there) instead of formatting it in the logger. The buffer is
+/// CPU-local and the test never yields, so concurrently running tests can't
+/// observe or replace it.
+#[test::test]
+async fn emergency_buffer_receives_report() {
+    panic_unwind::set_emergency_buffer(Box::leak(Box::new([0; 256])));
+    let caught = panic_unwind::catch_unwind(|| panic!("reported through the buffer"));
+    let buf = panic_unwind::take_emergency_buffer().expect("buffer was not returned");
+
+    assert!(caught.is_err(), "panic was not caught");
+    let needle = b"reported through the buffer";
+    assert!(
+        buf.windows(needle.len()).any(|window| window == needle),
+        "panic report was not formatted into the buffer"
+    );
+    assert!(panic_unwind::take_emergency_buffer().is_none());
+}
+
+/// The machine-readable format must put the whole panic on a single
+/// `key=value` line. (The handler's log output itself can't be captured
+/// here, so this checks the shared formatting through the caught panic. The
+/// global format is left alone, since other tests panic concurrently.)
+#[test::test]
+async fn machine_panic_format() {
+    let line = line!() + 1;
+    let caught = panic_unwind::catch_unwind_with_info(|| panic!("boom\nsecond line"));
+
+    let info = caught.err().expect("panic was not caught");
+    let location = info.location().expect("panic has no location");
+    assert_eq!(
+        format!("{}", info.display(PanicFormat::Machine)),
+        format!(
+            "PANIC file={} line={line} col={} msg=boom\\nsecond line",
+            file!(),
+            location.column()
+        )
+    );
+}
@@ -0,0 +1,327 @@
+// Copyright 2023-Present Jonas Kruckenberg
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use core::cell::Cell;
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use cpu_local::cpu_local;
+
+/// Maximum number of bytes of the panic message that are retained, longer messages are truncated.
+const MESSAGE_CAPACITY: usize = 256;
+/// Maximum number of bytes of the source file path that are retained.
+const FILE_CAPACITY: usize = 128;
+
+/// The format the panic handler reports panics in, see [`set_panic_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum PanicFormat {
+    /// `panicked at <file>:<line>:<col>:` followed by the message on the next line.
+    #[default]
+    Human = 0,
+    /// A single line of `key=value` fields, `PANIC file=<file> line=<line> col=<col> msg=<msg>`,
+    /// meant for log scrapers. The location fields are omitted if the panic has no location and
+    /// newlines in the message are escaped as `\n`, so `msg` always extends to the end of the line.
+    Machine = 1,
+}
+
+static PANIC_FORMAT: AtomicU8 = AtomicU8::new(PanicFormat::Human as u8);
+
+/// Sets the format the panic handler reports panics in, for all CPUs.
+pub fn set_panic_format(format: PanicFormat) {
+    PANIC_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Returns the format the panic handler currently reports panics in.
+#[must_use]
+pub fn panic_format() -> PanicFormat {
+    match PANIC_FORMAT.load(Ordering::Relaxed) {
+        1 => PanicFormat::Machine,
+        _ => PanicFormat::Human,
+    }
+}
+
+cpu_local! {
+    // Scratch buffer the panic handler formats reports into, see `set_emergency_buffer`.
+    static EMERGENCY_BUFFER: Cell<Option<&'static mut [u8]>> = const { Cell::new(None) };
+}
+
+/// Sets a scratch buffer the panic handler formats panic reports on the calling CPU into.
+///
+/// With a buffer set, the handler renders the report into `buf` first and passes the logger a
+/// plain string, so formatting the panic message needs no memory beyond `buf`. Reports longer
+/// than the buffer are truncated. The buffer is per-CPU, so panics on different CPUs never
+/// contend for (or overwrite) each other's buffer, and every CPU that should report this way
+/// needs its own.
+///
+/// Any previously set buffer of the calling CPU is replaced.
+pub fn set_emergency_buffer(buf: &'static mut [u8]) {
+    EMERGENCY_BUFFER.set(Some(buf));
+}
+
+/// Removes the buffer set by [`set_emergency_buffer`] on the calling CPU and returns it.
+///
+/// The buffer still contains the most recent report formatted into it, if any.
+pub fn take_emergency_buffer() -> Option<&'static mut [u8]> {
+    EMERGENCY_BUFFER.take()
+}
+
+/// Renders `args` into the calling CPU's emergency buffer and calls `f` with the result.
+///
+/// Returns `None` without calling `f` if no buffer is set.
+pub(crate) fn with_emergency_buffer<R>(
+    args: fmt::Arguments<'_>,
+    f: impl FnOnce(&str) -> R,
+) -> Option<R> {
+    // Taken out while in use, so a nested panic on this CPU logs directly instead of
+    // clobbering the report being formatted.
+    let buf = EMERGENCY_BUFFER.take()?;
+    let mut writer = SliceWriter { buf, len: 0 };
+    let _ = writer.write_fmt(args);
+    let res = f(writer.as_str());
+    EMERGENCY_BUFFER.set(Some(writer.buf));
+    Some(res)
+}
+
+/// An owned snapshot of a panic, returned by [`catch_unwind_with_info`](crate::catch_unwind_with_info).
+///
+/// Recording a panic must not allocate, so the message and file path are copied into fixed-size
+/// buffers and truncated if they don't fit.
+pub struct PanicInfoOwned {
+    message: StrBuf<MESSAGE_CAPACITY>,
+    location: Option<PanicLocation>,
+}
+
+/// The source location of a panic, see [`PanicInfoOwned::location`].
+pub struct PanicLocation {
+    file: StrBuf<FILE_CAPACITY>,
+    line: u32,
+    col: u32,
+}
+
+impl PanicInfoOwned {
+    /// Info for an unwind that didn't start at the panic handler, e.g. from
+    /// [`begin_unwind`](crate::begin_unwind) or [`resume_unwind`](crate::resume_unwind).
+    pub(crate) const fn unknown() -> Self {
+        Self {
+            message: StrBuf::new(),
+            location: None,
+        }
+    }
+
+    pub(crate) fn capture(info: &PanicInfo<'_>) -> Self {
+        let mut message = StrBuf::new();
+        let _ = write!(message, "{}", info.message());
+
+        let location = info.location().map(|location| {
+            let mut file = StrBuf::new();
+            let _ = file.write_str(location.file());
+            PanicLocation {
+                file,
+                line: location.line(),
+                col: location.column(),
+            }
+        });
+
+        Self { message, location }
+    }
+
+    /// The panic message, empty if the unwind didn't originate from a `panic!`.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        self.message.as_str()
+    }
+
+    /// Where the panic originated, `None` if the unwind didn't originate from a `panic!`.
+    #[must_use]
+    pub fn location(&self) -> Option<&PanicLocation> {
+        self.location.as_ref()
+    }
+
+    /// Returns an object that renders the panic in the given `format`, the same way the panic
+    /// handler reports it.
+    ///
+    /// The [`Display`](fmt::Display) impl of `PanicInfoOwned` itself always uses
+    /// [`PanicFormat::Human`].
+    #[must_use]
+    pub fn display(&self, format: PanicFormat) -> impl fmt::Display + '_ {
+        Formatted { info: self, format }
+    }
+}
+
+struct Formatted<'a> {
+    info: &'a PanicInfoOwned,
+    format: PanicFormat,
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.format {
+            PanicFormat::Human => fmt::Display::fmt(self.info, f),
+            PanicFormat::Machine => fmt::Display::fmt(
+                &MachineFormat {
+                    location: self
+                        .info
+                        .location
+                        .as_ref()
+                        .map(|location| (location.file(), location.line, location.col)),
+                    message: &self.info.message(),
+                },
+                f,
+            ),
+        }
+    }
+}
+
+/// Renders a panic in the [`PanicFormat::Machine`] format.
+pub(crate) struct MachineFormat<'a> {
+    pub(crate) location: Option<(&'a str, u32, u32)>,
+    pub(crate) message: &'a dyn fmt::Display,
+}
+
+impl fmt::Display for MachineFormat<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PANIC")?;
+        if let Some((file, line, col)) = self.location {
+            write!(f, " file={file} line={line} col={col}")?;
+        }
+        f.write_str(" msg=")?;
+        write!(EscapeNewlines(f), "{}", self.message)
+    }
+}
+
+/// Writes through to the inner writer, escaping line breaks so the output stays on one line.
+struct EscapeNewlines<W>(W);
+
+impl<W: Write> Write for EscapeNewlines<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                self.0.write_str("\\n")?;
+            }
+            self.0.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PanicInfoOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicInfoOwned")
+            .field("message", &self.message())
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl fmt::Display for PanicInfoOwned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("panicked")?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        if !self.message.as_str().is_empty() {
+            write!(f, ":\n{}", self.message())?;
+        }
+        Ok(())
+    }
+}
+
+impl PanicLocation {
+    /// The path of the source file, possibly truncated.
+    #[must_use]
+    pub fn file(&self) -> &str {
+        self.file.as_str()
+    }
+
+    /// The line number.
+    #[must_use]
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column number.
+    #[must_use]
+    pub fn column(&self) -> u32 {
+        self.col
+    }
+}
+
+impl fmt::Debug for PanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicLocation")
+            .field("file", &self.file())
+            .field("line", &self.line)
+            .field("col", &self.col)
+            .finish()
+    }
+}
+
+impl fmt::Display for PanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file(), self.line, self.col)
+    }
+}
+
+/// A fixed-capacity string that truncates (on a `char` boundary) instead of failing.
+struct StrBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> StrBuf<N> {
+    const fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        // Safety: `write_str` only ever copies whole `char`s into the buffer.
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+}
+
+impl<const N: usize> Write for StrBuf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_truncated(&mut self.buf, &mut self.len, s);
+        Ok(())
+    }
+}
+
+/// A string writer over a `'static` buffer that truncates (on a `char` boundary) instead of failing.
+struct SliceWriter {
+    buf: &'static mut [u8],
+    len: usize,
+}
+
+impl SliceWriter {
+    fn as_str(&self) -> &str {
+        // Safety: `write_str` only ever copies whole `char`s into the buffer.
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+}
+
+impl Write for SliceWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_truncated(self.buf, &mut self.len, s);
+        Ok(())
+    }
+}
+
+/// Appends as much of `s` as fits to `buf[..*len]`, cutting it on a `char` boundary.
+fn write_truncated(buf: &mut [u8], len: &mut usize, s: &str) {
+    let mut n = s.len().min(buf.len() - *len);
+    while !s.is_char_boundary(n) {
+        n -= 1;
+    }
+
+    buf[*len..*len + n].copy_from_slice(&s.as_bytes()[..n]);
+    *len += n;
+}
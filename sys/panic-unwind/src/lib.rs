@@ -10,13 +10,21 @@
 #![feature(panic_can_unwind)]
 #![feature(thread_local)]
 
+mod info;
+
 use core::cell::Cell;
-use core::ptr;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::{mem, ptr};
 
 use abort::abort;
 use cpu_local::cpu_local;
 use unwind::UnwindException;
 
+pub use crate::info::{
+    PanicFormat, PanicInfoOwned, PanicLocation, panic_format, set_emergency_buffer,
+    set_panic_format, take_emergency_buffer,
+};
+
 // Single exception header shared by every in-flight panic.
 // We need _some_ pointer to pass through the system (that is expected by the landing pad code)
 // so we use this static so we can check against a known address AND the class field.
@@ -27,8 +35,16 @@ cpu_local! {
     // In-flight panic count, and whether a handler is currently reporting
     // one (logging + backtrace).
     static PANIC_STATE: Cell<(usize, bool)> = Cell::new((0, false));
+    // What the panic handler recorded about the most recent panic, claimed by the innermost
+    // `catch_unwind_with_info` that catches it. Plain `catch_unwind` leaves it alone to keep
+    // the common path cheap.
+    static LAST_PANIC: Cell<Option<PanicInfoOwned>> = const { Cell::new(None) };
+    // Nesting depth of `with_abort_on_panic` scopes.
+    static ABORT_SCOPES: Cell<usize> = const { Cell::new(0) };
 }
 
+static ALWAYS_ABORT: AtomicBool = AtomicBool::new(false);
+
 /// Whether the current CPU is unwinding because of a panic.
 #[inline]
 #[must_use]
@@ -36,6 +52,17 @@ pub fn panicking() -> bool {
     PANIC_STATE.get().0 > 0
 }
 
+/// The number of panics currently unwinding on this CPU.
+///
+/// This is `0` during normal execution, `1` while a panic unwinds, and greater than `1` if a
+/// `Drop` impl run by that unwind panicked itself. The count is per-CPU and only reflects unwinds
+/// that haven't been caught yet.
+#[inline]
+#[must_use]
+pub fn panic_count() -> usize {
+    PANIC_STATE.get().0
+}
+
 fn increase() {
     let (count, reporting) = PANIC_STATE.get();
     // A panic raised while a handler is reporting can only have come from the
@@ -69,6 +96,110 @@ where
     unwind::catch_unwind(f).map_err(|_| decrease())
 }
 
+/// Invokes a closure, catching an unwinding panic if one occurs and returning the panic's message
+/// and location.
+///
+/// Unwinds that weren't started by a `panic!` (e.g. [`begin_unwind`] or [`resume_unwind`]) carry
+/// no message or location.
+///
+/// # Errors
+///
+/// Returns `Err` if the closure panicked.
+pub fn catch_unwind_with_info<F, R>(f: F) -> Result<R, PanicInfoOwned>
+where
+    F: FnOnce() -> R + core::panic::UnwindSafe,
+{
+    // If we're being called from a `Drop` impl during an unwind, stash that unwind's info so a
+    // panic caught here doesn't clobber it.
+    let outer = LAST_PANIC.take();
+    let res = unwind::catch_unwind(f).map_err(|_| {
+        decrease();
+        LAST_PANIC.take().unwrap_or(PanicInfoOwned::unknown())
+    });
+    LAST_PANIC.set(outer);
+    res
+}
+
+/// Invokes a closure, aborting if it unwinds.
+///
+/// This is for code that must not be unwound through, e.g. because it leaves data structures in an
+/// inconsistent state half-way through. See [`AbortOnDrop`].
+pub fn abort_on_unwind<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let guard = AbortOnDrop::new();
+    let res = f();
+    mem::forget(guard);
+    res
+}
+
+/// A guard that aborts if it is dropped by an unwind.
+///
+/// The guard remembers the [`panic_count`] at creation, so creating one inside a `Drop` impl run by
+/// an ongoing unwind is fine, only a panic raised *after* the guard was created triggers the abort.
+/// Dropping the guard normally is a no-op.
+#[derive(Debug)]
+pub struct AbortOnDrop {
+    panic_count: usize,
+}
+
+impl AbortOnDrop {
+    /// Creates a guard that aborts if a panic raised from now on unwinds through it.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            panic_count: panic_count(),
+        }
+    }
+}
+
+impl Default for AbortOnDrop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if panic_count() > self.panic_count {
+            log::error!("panic unwound through an `AbortOnDrop` guard. aborting.");
+            abort();
+        }
+    }
+}
+
+/// Makes every future panic on all CPUs abort instead of unwind. This cannot be undone.
+pub fn always_abort() {
+    ALWAYS_ABORT.store(true, Ordering::Relaxed);
+}
+
+/// Invokes a closure, making any panic on this CPU abort instead of unwind while it runs.
+///
+/// Unlike [`abort_on_unwind`], which aborts once an unwind *reaches* it and so after the inner
+/// frames' `Drop` impls already ran, this aborts before unwinding begins, so no destructor runs at
+/// all. This also applies to [`resume_unwind`] and [`begin_unwind`]. Scopes may be nested.
+pub fn with_abort_on_panic<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    ABORT_SCOPES.set(ABORT_SCOPES.get() + 1);
+    let res = f();
+    ABORT_SCOPES.set(ABORT_SCOPES.get() - 1);
+    res
+}
+
+/// Whether a panic raised on this CPU right now would unwind, i.e. neither [`always_abort`] was
+/// called nor is the CPU inside a [`with_abort_on_panic`] scope.
+///
+/// Note that a panic may still abort if it can't unwind for other reasons, e.g. because it was
+/// raised in a `nounwind` function.
+#[inline]
+#[must_use]
+pub fn can_unwind() -> bool {
+    !ALWAYS_ABORT.load(Ordering::Relaxed) && ABORT_SCOPES.get() == 0
+}
+
 /// Resume an unwind previously caught with [`catch_unwind`].
 pub fn resume_unwind() -> ! {
     increase();
@@ -95,7 +226,21 @@ fn panic_handler(info: &core::panic::PanicInfo<'_>) -> ! {
     // straight back into this handler; `increase` aborts on it while set.
     set_reporting(true);
 
-    log::error!("CPU {info}");
+    LAST_PANIC.set(Some(PanicInfoOwned::capture(info)));
+
+    let machine = info::MachineFormat {
+        location: info
+            .location()
+            .map(|location| (location.file(), location.line(), location.column())),
+        message: &info.message(),
+    };
+    let report = match panic_format() {
+        PanicFormat::Human => format_args!("CPU {info}"),
+        PanicFormat::Machine => format_args!("{machine}"),
+    };
+    if info::with_emergency_buffer(report, |report| log::error!("{report}")).is_none() {
+        log::error!("{report}");
+    }
 
     // FIXME 32 seems adequate for unoptimized builds where the callstack can get quite deep
     //  but (at least at the moment) is absolute overkill for optimized builds. Sadly there
@@ -131,6 +276,11 @@ fn panic_handler(info: &core::panic::PanicInfo<'_>) -> ! {
 #[inline(never)]
 #[unsafe(no_mangle)]
 fn rust_panic(regs: unwind::Registers, pc: usize) -> ! {
+    if !can_unwind() {
+        log::error!("unwinding is disabled on this cpu. aborting.");
+        abort();
+    }
+
     // NB: `PANIC_EXCEPTION` is actually treated as immutable, nothing ever writes through this
     // but the rustc `intrinsics::catch_unwind` require a mut ptr.
     let exception = ptr::from_ref(&PANIC_EXCEPTION).cast_mut();